@@ -0,0 +1,92 @@
+//! Multi-sensor manager
+use crate::{DynamicSetting, Error, IntegrationTime, Measurement, Mode, Veml6075};
+use embedded_hal::blocking::i2c::{Write, WriteRead};
+
+/// Manager for several Veml6075 devices.
+///
+/// Configuration is applied to all devices in order and measurements are
+/// read from all devices at once. The devices can be behind a multiplexer
+/// (see [`MuxedVeml6075`](type.MuxedVeml6075.html)) or on separate buses.
+///
+/// Operations stop at the first error, so the devices after the failing one
+/// are left unchanged.
+#[derive(Debug)]
+pub struct Veml6075Array<I2C, const N: usize> {
+    sensors: [Veml6075<I2C>; N],
+}
+
+impl<I2C, const N: usize> Veml6075Array<I2C, N> {
+    /// Create a new manager for the given devices.
+    pub fn new(sensors: [Veml6075<I2C>; N]) -> Self {
+        Veml6075Array { sensors }
+    }
+
+    /// Destroy the manager, return the device instances.
+    pub fn destroy(self) -> [Veml6075<I2C>; N] {
+        self.sensors
+    }
+
+    /// Access a single device.
+    pub fn sensor_mut(&mut self, index: usize) -> Option<&mut Veml6075<I2C>> {
+        self.sensors.get_mut(index)
+    }
+}
+
+impl<I2C, E, const N: usize> Veml6075Array<I2C, N>
+where
+    I2C: Write<Error = E>,
+{
+    /// Enable all devices.
+    pub fn enable(&mut self) -> Result<(), Error<E>> {
+        self.sensors.iter_mut().try_for_each(|s| s.enable())
+    }
+
+    /// Disable all devices (shutdown).
+    pub fn disable(&mut self) -> Result<(), Error<E>> {
+        self.sensors.iter_mut().try_for_each(|s| s.disable())
+    }
+
+    /// Set the operating mode of all devices.
+    pub fn set_mode(&mut self, mode: Mode) -> Result<(), Error<E>> {
+        self.sensors.iter_mut().try_for_each(|s| s.set_mode(mode))
+    }
+
+    /// Trigger a measurement on all devices when on active force (one-shot) mode.
+    pub fn trigger_measurement(&mut self) -> Result<(), Error<E>> {
+        self.sensors
+            .iter_mut()
+            .try_for_each(|s| s.trigger_measurement())
+    }
+
+    /// Set the integration time of all devices.
+    pub fn set_integration_time(&mut self, it: IntegrationTime) -> Result<(), Error<E>> {
+        self.sensors
+            .iter_mut()
+            .try_for_each(|s| s.set_integration_time(it))
+    }
+
+    /// Set the dynamic setting of all devices.
+    pub fn set_dynamic_setting(&mut self, ds: DynamicSetting) -> Result<(), Error<E>> {
+        self.sensors
+            .iter_mut()
+            .try_for_each(|s| s.set_dynamic_setting(ds))
+    }
+}
+
+impl<I2C, E, const N: usize> Veml6075Array<I2C, N>
+where
+    I2C: WriteRead<Error = E>,
+{
+    /// Read the calibrated measurements of all devices.
+    pub fn read_all(&mut self) -> Result<[Measurement; N], Error<E>> {
+        let mut measurements = [Measurement {
+            uva: 0.0,
+            uvb: 0.0,
+            uv_index: 0.0,
+        }; N];
+        for (m, sensor) in measurements.iter_mut().zip(self.sensors.iter_mut()) {
+            *m = sensor.read()?;
+        }
+        Ok(measurements)
+    }
+}
@@ -0,0 +1,83 @@
+//! Closed-loop auto-exposure
+use crate::{Error, IntegrationTime, RawMeasurement, Veml6075};
+use core::convert::TryFrom;
+use embedded_hal::blocking::i2c::Write;
+
+/// Controller adjusting the integration time to keep the UVA counts within
+/// a target band.
+///
+/// The band is given as fractions of full scale. After every reading, the
+/// integration time is doubled if the UVA counts are below the band and
+/// doubling them stays below its upper limit, or halved if they are above
+/// the band. This maximizes the resolution across the large dynamic range
+/// between dawn and noon.
+///
+/// Note that the counts, and thus the calibrated values, scale with the
+/// integration time.
+///
+/// ```
+/// use veml6075::{AutoExposure, IntegrationTime, RawMeasurement};
+/// # use embedded_hal_mock::i2c::{Mock as I2cMock, Transaction as I2cTrans};
+/// # use veml6075::{Calibration, Veml6075};
+/// # let mut sensor = Veml6075::new(
+/// #     I2cMock::new(&[I2cTrans::write(0x10, vec![0, 0b0001_0001, 0])]),
+/// #     Calibration::default(),
+/// # );
+///
+/// let auto = AutoExposure::new(0.2, 0.8);
+/// let raw = RawMeasurement { uva: 1000, uvb: 500, uvcomp1: 100, uvcomp2: 50 };
+/// let changed = auto.update(&mut sensor, &raw).unwrap();
+/// assert_eq!(Some(IntegrationTime::Ms100), changed);
+/// # sensor.destroy().done();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AutoExposure {
+    low: u32,
+    high: u32,
+}
+
+impl Default for AutoExposure {
+    /// Target band between 20 % and 80 % of full scale.
+    fn default() -> Self {
+        AutoExposure::new(0.2, 0.8)
+    }
+}
+
+impl AutoExposure {
+    /// Create a controller for the target band given as fractions of full scale.
+    pub fn new(low: f32, high: f32) -> Self {
+        AutoExposure {
+            low: (low * 65535.0) as u32,
+            high: (high * 65535.0) as u32,
+        }
+    }
+
+    /// Adjust the integration time for the counts read with the current one.
+    ///
+    /// Returns the new integration time if it was changed.
+    pub fn update<I2C, E>(
+        &self,
+        sensor: &mut Veml6075<I2C>,
+        raw: &RawMeasurement,
+    ) -> Result<Option<IntegrationTime>, Error<E>>
+    where
+        I2C: Write<Error = E>,
+    {
+        let uva = u32::from(raw.uva);
+        let it = u8::from(sensor.integration_time());
+        let new_it = if uva > self.high && it > 0 {
+            it - 1
+        } else if uva < self.low && uva * 2 <= self.high {
+            it + 1
+        } else {
+            it
+        };
+        match IntegrationTime::try_from(new_it) {
+            Ok(new_it) if u8::from(new_it) != it => {
+                sensor.set_integration_time(new_it)?;
+                Ok(Some(new_it))
+            }
+            _ => Ok(None),
+        }
+    }
+}
@@ -0,0 +1,393 @@
+//! Command-line tool to validate VEML6075 hardware on Linux.
+//!
+//! Run `veml6075-cli help` for the usage.
+
+use linux_embedded_hal::{Delay, I2cdev};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::Path;
+use std::{env, process, thread, time::Duration};
+use veml6075::{
+    Clock, DynamicSetting, IntegrationTime, Measurement, Mode, RetryPolicy, SystemClock, Veml6075,
+};
+
+const USAGE: &str = "Usage: veml6075-cli [options] <command>
+
+Commands:
+  read                         Read calibrated UVA, UVB and UV index
+  raw                          Read raw UVA, UVB, UVcomp1 and UVcomp2 counts
+  id                           Read the device ID
+  enable                       Enable the sensor
+  disable                      Disable the sensor (shutdown)
+  config set-it <ms>           Set integration time (50, 100, 200, 400, 800)
+  config set-dynamic <value>   Set dynamic setting (normal, high)
+  config set-mode <mode>       Set operating mode (continuous, active-force)
+  monitor                      Sample continuously until interrupted
+  export                       Serve the measurements as Prometheus gauges
+  iio                          Mirror the measurements into files in the
+                               Linux industrial I/O (IIO) sysfs layout
+  help                         Print this message
+
+Options:
+  --device <path>              I2C device (default: /dev/i2c-1)
+  --interval <seconds>         Monitor sampling interval (default: 1)
+  --format <csv|json>          Monitor output format (default: csv)
+  --output <file>              Monitor output file (default: standard output)
+  --max-size <bytes>           Rotate the output file to <file>.1 when it
+                               exceeds this size (default: 10000000)
+  --listen <address>           Export HTTP server address (default: 0.0.0.0:9101)
+  --dir <path>                 IIO output directory (default: /run/veml6075)";
+
+struct Options {
+    device: String,
+    interval: u64,
+    format: Format,
+    output: Option<String>,
+    max_size: u64,
+    listen: String,
+    dir: String,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Format {
+    Csv,
+    Json,
+}
+
+fn main() {
+    let mut options = Options {
+        device: String::from("/dev/i2c-1"),
+        interval: 1,
+        format: Format::Csv,
+        output: None,
+        max_size: 10_000_000,
+        listen: String::from("0.0.0.0:9101"),
+        dir: String::from("/run/veml6075"),
+    };
+    let mut args = Vec::new();
+    let mut iter = env::args().skip(1);
+    while let Some(arg) = iter.next() {
+        if !arg.starts_with("--") {
+            args.push(arg);
+            continue;
+        }
+        let value = iter
+            .next()
+            .unwrap_or_else(|| fail(&format!("Missing value for {}", arg)));
+        match arg.as_str() {
+            "--device" => options.device = value,
+            "--interval" => options.interval = parse(&arg, &value),
+            "--format" => {
+                options.format = match value.as_str() {
+                    "csv" => Format::Csv,
+                    "json" => Format::Json,
+                    _ => fail(&format!("Invalid format: {}", value)),
+                }
+            }
+            "--output" => options.output = Some(value),
+            "--max-size" => options.max_size = parse(&arg, &value),
+            "--listen" => options.listen = value,
+            "--dir" => options.dir = value,
+            _ => fail(&format!("Unknown option: {}\n\n{}", arg, USAGE)),
+        }
+    }
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    if args.is_empty() || args[0] == "help" {
+        println!("{}", USAGE);
+        return;
+    }
+    let mut sensor = Veml6075::open(&options.device)
+        .unwrap_or_else(|e| fail(&format!("{}: {}", options.device, e)));
+    sensor
+        .load_config()
+        .unwrap_or_else(|e| fail(&format!("Error reading configuration: {}", e)));
+    let result = match args.as_slice() {
+        ["read"] => sensor.enable().and_then(|_| {
+            let m = sensor.iter_measurements(&mut Delay).next().unwrap()?;
+            println!("{}", m);
+            Ok(())
+        }),
+        ["raw"] => sensor.enable().and_then(|_| {
+            let mut delay = Delay;
+            sensor.iter_measurements(&mut delay).next().unwrap()?;
+            let raw = sensor.read_raw()?;
+            println!(
+                "UVA: {}, UVB: {}, UVcomp1: {}, UVcomp2: {}",
+                raw.uva, raw.uvb, raw.uvcomp1, raw.uvcomp2
+            );
+            Ok(())
+        }),
+        ["id"] => sensor.read_device_id().map(|id| println!("{:#06x}", id)),
+        ["enable"] => sensor.enable(),
+        ["disable"] => sensor.disable(),
+        ["config", "set-it", value] => {
+            let it = match *value {
+                "50" => IntegrationTime::Ms50,
+                "100" => IntegrationTime::Ms100,
+                "200" => IntegrationTime::Ms200,
+                "400" => IntegrationTime::Ms400,
+                "800" => IntegrationTime::Ms800,
+                _ => fail(&format!("Invalid integration time: {}", value)),
+            };
+            sensor.set_integration_time(it)
+        }
+        ["config", "set-dynamic", value] => {
+            let ds = match *value {
+                "normal" => DynamicSetting::Normal,
+                "high" => DynamicSetting::High,
+                _ => fail(&format!("Invalid dynamic setting: {}", value)),
+            };
+            sensor.set_dynamic_setting(ds)
+        }
+        ["config", "set-mode", value] => {
+            let mode = match *value {
+                "continuous" => Mode::Continuous,
+                "active-force" => Mode::ActiveForce,
+                _ => fail(&format!("Invalid mode: {}", value)),
+            };
+            sensor.set_mode(mode)
+        }
+        ["monitor"] => monitor(&mut sensor, &options),
+        ["export"] => export(&mut sensor, &options),
+        ["iio"] => iio(&mut sensor, &options),
+        _ => fail(USAGE),
+    };
+    if let Err(e) = result {
+        fail(&format!("Error: {}", e));
+    }
+}
+
+/// Sample until interrupted. Bus errors are reported and the sensor is
+/// recovered on the next sample instead of stopping the survey.
+fn monitor(sensor: &mut Veml6075<I2cdev>, options: &Options) -> ! {
+    sensor.set_retry_policy(RetryPolicy {
+        retries: 3,
+        on_retry: Some(|_| thread::sleep(Duration::from_millis(10))),
+    });
+    if let Err(e) = sensor.enable() {
+        fail(&format!("Error: {}", e));
+    }
+    let mut output = Output::open(options).unwrap_or_else(|e| fail(&format!("{}", e)));
+    let mut failed = false;
+    loop {
+        if failed {
+            failed = sensor.recover().is_err();
+        }
+        if !failed {
+            let at = SystemClock.now_ms();
+            match sensor.iter_measurements(&mut Delay).next().unwrap() {
+                Ok(m) => output
+                    .write(&m, at)
+                    .unwrap_or_else(|e| fail(&format!("Error writing output: {}", e))),
+                Err(e) => {
+                    eprintln!("Error reading measurement: {}", e);
+                    failed = true;
+                }
+            }
+        }
+        thread::sleep(Duration::from_secs(options.interval));
+    }
+}
+
+/// Serve a fresh measurement on every HTTP request until interrupted.
+fn export(sensor: &mut Veml6075<I2cdev>, options: &Options) -> ! {
+    if let Err(e) = sensor.enable() {
+        fail(&format!("Error: {}", e));
+    }
+    let listener = TcpListener::bind(&options.listen)
+        .unwrap_or_else(|e| fail(&format!("{}: {}", options.listen, e)));
+    for stream in listener.incoming() {
+        let result = stream.and_then(|stream| serve(sensor, stream));
+        if let Err(e) = result {
+            eprintln!("Error serving request: {}", e);
+        }
+    }
+    process::exit(0)
+}
+
+fn serve(sensor: &mut Veml6075<I2cdev>, mut stream: TcpStream) -> io::Result<()> {
+    // Skip the request headers. Any path returns the metrics.
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut line = String::new();
+    while reader.read_line(&mut line)? > 2 {
+        line.clear();
+    }
+    let (status, body) = match sensor.iter_measurements(&mut Delay).next().unwrap() {
+        Ok(m) => {
+            let mut body = String::new();
+            m.write_prometheus(&mut body).unwrap();
+            ("200 OK", body)
+        }
+        Err(e) => {
+            eprintln!("Error reading measurement: {}", e);
+            let _ = sensor.recover();
+            ("503 Service Unavailable", format!("{}\n", e))
+        }
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: text/plain; version=0.0.4\r\n\
+         Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )
+}
+
+/// Write the measurements into files like an IIO device until interrupted.
+///
+/// Writing a new value (in seconds) to `in_intensity_integration_time`
+/// changes the integration time, as with IIO.
+fn iio(sensor: &mut Veml6075<I2cdev>, options: &Options) -> ! {
+    let dir = Path::new(&options.dir);
+    let result = fs::create_dir_all(dir)
+        .and_then(|_| write_attribute(dir, "name", "veml6075"))
+        .and_then(|_| write_it_attribute(dir, sensor.integration_time()));
+    if let Err(e) = result {
+        fail(&format!("{}: {}", options.dir, e));
+    }
+    if let Err(e) = sensor.enable() {
+        fail(&format!("Error: {}", e));
+    }
+    loop {
+        let current = sensor.integration_time();
+        let requested = fs::read_to_string(dir.join("in_intensity_integration_time"))
+            .ok()
+            .and_then(|value| parse_it_seconds(value.trim()));
+        if let Some(it) = requested.filter(|it| *it != current) {
+            if let Err(e) = sensor.set_integration_time(it) {
+                eprintln!("Error setting integration time: {}", e);
+            }
+        }
+        let result = sensor.wait_integration(&mut Delay);
+        match result.and_then(|_| sensor.read_full()) {
+            Ok(full) => {
+                let (raw, m) = (full.raw, full.measurement);
+                let result = write_attribute(dir, "in_intensity_uva_raw", &raw.uva.to_string())
+                    .and_then(|_| {
+                        write_attribute(dir, "in_intensity_uvb_raw", &raw.uvb.to_string())
+                    })
+                    .and_then(|_| {
+                        write_attribute(dir, "in_intensity_uva_input", &m.uva.to_string())
+                    })
+                    .and_then(|_| {
+                        write_attribute(dir, "in_intensity_uvb_input", &m.uvb.to_string())
+                    })
+                    .and_then(|_| write_attribute(dir, "in_uvindex_input", &m.uv_index.to_string()))
+                    .and_then(|_| write_it_attribute(dir, sensor.integration_time()));
+                if let Err(e) = result {
+                    fail(&format!("{}: {}", options.dir, e));
+                }
+            }
+            Err(e) => {
+                eprintln!("Error reading measurement: {}", e);
+                let _ = sensor.recover();
+            }
+        }
+        thread::sleep(Duration::from_secs(options.interval));
+    }
+}
+
+fn parse_it_seconds(value: &str) -> Option<IntegrationTime> {
+    let ms = (value.parse::<f32>().ok()? * 1000.0).round() as u32;
+    match ms {
+        50 => Some(IntegrationTime::Ms50),
+        100 => Some(IntegrationTime::Ms100),
+        200 => Some(IntegrationTime::Ms200),
+        400 => Some(IntegrationTime::Ms400),
+        800 => Some(IntegrationTime::Ms800),
+        _ => None,
+    }
+}
+
+fn write_it_attribute(dir: &Path, it: IntegrationTime) -> io::Result<()> {
+    let ms = 50 << u8::from(it);
+    let value = format!("{}.{:06}", ms / 1000, (ms % 1000) * 1000);
+    write_attribute(dir, "in_intensity_integration_time", &value)
+}
+
+/// Replace the file atomically so that readers never see partial values.
+fn write_attribute(dir: &Path, name: &str, value: &str) -> io::Result<()> {
+    let tmp = dir.join(format!(".{}.tmp", name));
+    fs::write(&tmp, format!("{}\n", value))?;
+    fs::rename(tmp, dir.join(name))
+}
+
+struct Output {
+    path: Option<String>,
+    file: Option<File>,
+    format: Format,
+    max_size: u64,
+}
+
+impl Output {
+    fn open(options: &Options) -> io::Result<Self> {
+        let mut output = Output {
+            path: options.output.clone(),
+            file: None,
+            format: options.format,
+            max_size: options.max_size,
+        };
+        output.reopen()?;
+        Ok(output)
+    }
+
+    fn reopen(&mut self) -> io::Result<()> {
+        let mut empty = true;
+        if let Some(path) = &self.path {
+            let file = OpenOptions::new().create(true).append(true).open(path)?;
+            empty = file.metadata()?.len() == 0;
+            self.file = Some(file);
+        }
+        if empty && self.format == Format::Csv {
+            let mut header = String::new();
+            Measurement::write_csv_header(&mut header).unwrap();
+            self.write_line(&header)?;
+        }
+        Ok(())
+    }
+
+    fn write(&mut self, m: &Measurement, at: u64) -> io::Result<()> {
+        if let (Some(path), Some(file)) = (&self.path, &self.file) {
+            if file.metadata()?.len() >= self.max_size {
+                fs::rename(path, format!("{}.1", path))?;
+                self.reopen()?;
+            }
+        }
+        let mut line = String::new();
+        match self.format {
+            Format::Csv => m.write_csv(&mut line, at).unwrap(),
+            Format::Json => {
+                let mut buffer = [0; 128];
+                let len = m.write_json(&mut buffer).unwrap();
+                let json = std::str::from_utf8(&buffer[..len]).unwrap();
+                line = format!("{{\"timestamp\":{},\"measurement\":{}}}\n", at, json);
+            }
+        }
+        self.write_line(&line)
+    }
+
+    fn write_line(&mut self, line: &str) -> io::Result<()> {
+        match &mut self.file {
+            Some(file) => file.write_all(line.as_bytes()),
+            None => {
+                let stdout = io::stdout();
+                let mut stdout = stdout.lock();
+                stdout.write_all(line.as_bytes())?;
+                stdout.flush()
+            }
+        }
+    }
+}
+
+fn parse(option: &str, value: &str) -> u64 {
+    value
+        .parse()
+        .unwrap_or_else(|_| fail(&format!("Invalid value for {}: {}", option, value)))
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("{}", message);
+    process::exit(1);
+}
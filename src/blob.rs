@@ -0,0 +1,146 @@
+//! Calibration persistence
+//!
+//! The calibration is stored as a format version byte followed by the
+//! coefficients as little-endian `f32` in declaration order and an integrity
+//! check:
+//!
+//! | Version | Integrity check                                          | Size     |
+//! |---------|----------------------------------------------------------|----------|
+//! | 1       | Two's complement of the wrapping sum of the bytes (`u8`) | 46 bytes |
+//! | 2       | CRC-16/CCITT-FALSE of the bytes (`u16`, little-endian)   | 47 bytes |
+//!
+//! Blobs are always written in the current version. Blobs of older versions
+//! are still decoded and can be rewritten in the current version with
+//! [`migrate_calibration_blob()`](fn.migrate_calibration_blob.html).
+use crate::Calibration;
+
+/// Size of a calibration blob in the current format version in bytes.
+pub const CALIBRATION_BLOB_LEN: usize = 3 + 4 * COEFFICIENT_COUNT;
+
+/// Current calibration blob format version.
+pub const CALIBRATION_BLOB_VERSION: u8 = 2;
+
+const COEFFICIENT_COUNT: usize = 11;
+const V1_LEN: usize = 2 + 4 * COEFFICIENT_COUNT;
+
+/// Calibration blob decoding error
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum BlobError {
+    /// The blob is too short
+    Length,
+    /// The format version is not supported
+    Version(u8),
+    /// The checksum does not match, the blob is corrupted
+    Checksum,
+}
+
+impl Calibration {
+    /// Encode the calibration into a blob for storage, for example in EEPROM.
+    pub fn to_bytes(&self) -> [u8; CALIBRATION_BLOB_LEN] {
+        let mut data = [0; CALIBRATION_BLOB_LEN];
+        data[0] = CALIBRATION_BLOB_VERSION;
+        for (chunk, value) in data[1..]
+            .chunks_exact_mut(4)
+            .zip(self.coefficients().iter())
+        {
+            chunk.copy_from_slice(&value.to_bits().to_le_bytes());
+        }
+        let crc = crc16(&data[..CALIBRATION_BLOB_LEN - 2]);
+        data[CALIBRATION_BLOB_LEN - 2..].copy_from_slice(&crc.to_le_bytes());
+        data
+    }
+
+    /// Decode a calibration blob of any supported format version.
+    ///
+    /// Trailing bytes after the blob are ignored. Corrupted blobs are rejected.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, BlobError> {
+        let version = *data.first().ok_or(BlobError::Length)?;
+        let len = match version {
+            1 => V1_LEN,
+            2 => CALIBRATION_BLOB_LEN,
+            _ => return Err(BlobError::Version(version)),
+        };
+        if data.len() < len {
+            return Err(BlobError::Length);
+        }
+        let data = &data[..len];
+        let valid = match version {
+            1 => checksum(&data[..len - 1]) == data[len - 1],
+            _ => crc16(&data[..len - 2]).to_le_bytes() == data[len - 2..],
+        };
+        if !valid {
+            return Err(BlobError::Checksum);
+        }
+        let mut c = [0.0; COEFFICIENT_COUNT];
+        for (value, chunk) in c.iter_mut().zip(data[1..].chunks_exact(4)) {
+            *value = f32::from_bits(u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]));
+        }
+        Ok(Calibration {
+            uva_visible: c[0],
+            uva_ir: c[1],
+            uvb_visible: c[2],
+            uvb_ir: c[3],
+            uva_responsivity: c[4],
+            uvb_responsivity: c[5],
+            angular_correction: c[6],
+            uva_transmission: c[7],
+            uvb_transmission: c[8],
+            uva_gain: c[9],
+            uvb_gain: c[10],
+        })
+    }
+
+    fn coefficients(&self) -> [f32; COEFFICIENT_COUNT] {
+        [
+            self.uva_visible,
+            self.uva_ir,
+            self.uvb_visible,
+            self.uvb_ir,
+            self.uva_responsivity,
+            self.uvb_responsivity,
+            self.angular_correction,
+            self.uva_transmission,
+            self.uvb_transmission,
+            self.uva_gain,
+            self.uvb_gain,
+        ]
+    }
+}
+
+/// Rewrite a calibration blob of any supported version in the current version.
+///
+/// Returns `None` if the blob is already in the current version, so that it
+/// only needs to be written back to storage if something changed.
+pub fn migrate_calibration_blob(
+    data: &[u8],
+) -> Result<Option<[u8; CALIBRATION_BLOB_LEN]>, BlobError> {
+    let calibration = Calibration::from_bytes(data)?;
+    if data[0] == CALIBRATION_BLOB_VERSION {
+        Ok(None)
+    } else {
+        Ok(Some(calibration.to_bytes()))
+    }
+}
+
+fn checksum(data: &[u8]) -> u8 {
+    data.iter()
+        .fold(0_u8, |sum, b| sum.wrapping_add(*b))
+        .wrapping_neg()
+}
+
+/// CRC-16/CCITT-FALSE
+fn crc16(data: &[u8]) -> u16 {
+    let mut crc = 0xFFFF_u16;
+    for byte in data {
+        crc ^= u16::from(*byte) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                crc << 1 ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
@@ -0,0 +1,65 @@
+//! Borrowed I²C bus
+use crate::{Calibration, Veml6075};
+use embedded_hal::blocking::i2c::{Write, WriteRead};
+
+/// I²C bus adapter borrowing the bus instead of owning it.
+///
+/// This allows using the driver for a short time without giving up the
+/// bus ownership. See [`new_borrowed()`](struct.Veml6075.html#method.new_borrowed).
+#[derive(Debug)]
+pub struct BorrowedI2c<'a, I2C> {
+    i2c: &'a mut I2C,
+}
+
+impl<'a, I2C> BorrowedI2c<'a, I2C> {
+    /// Create a new adapter borrowing the I²C bus.
+    pub fn new(i2c: &'a mut I2C) -> Self {
+        BorrowedI2c { i2c }
+    }
+}
+
+impl<'a, I2C, E> Veml6075<BorrowedI2c<'a, I2C>>
+where
+    I2C: Write<Error = E>,
+{
+    /// Create new instance of the Veml6075 device borrowing the I²C bus.
+    ///
+    /// The driver can be dropped and created again later. The cached
+    /// configuration can then be restored with `load_config()`.
+    ///
+    /// ```no_run
+    /// use linux_embedded_hal::I2cdev;
+    /// use veml6075::{Calibration, Veml6075};
+    ///
+    /// let mut dev = I2cdev::new("/dev/i2c-1").unwrap();
+    /// {
+    ///     let mut sensor = Veml6075::new_borrowed(&mut dev, Calibration::default());
+    ///     sensor.enable().unwrap();
+    /// }
+    /// // `dev` can be used again here
+    /// ```
+    pub fn new_borrowed(i2c: &'a mut I2C, calibration: Calibration) -> Self {
+        Veml6075::new(BorrowedI2c::new(i2c), calibration)
+    }
+}
+
+impl<I2C: Write> Write for BorrowedI2c<'_, I2C> {
+    type Error = I2C::Error;
+
+    fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.i2c.write(address, bytes)
+    }
+}
+
+impl<I2C: WriteRead> WriteRead for BorrowedI2c<'_, I2C> {
+    type Error = I2C::Error;
+
+    fn write_read(
+        &mut self,
+        address: u8,
+        bytes: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.i2c.write_read(address, bytes, buffer)
+    }
+}
@@ -0,0 +1,66 @@
+//! BTHome v2 BLE advertisement encoding.
+//!
+//! A measurement is encoded as an unencrypted
+//! [BTHome v2](https://bthome.io/format/) service data AD structure which
+//! can be placed directly in the advertising data of a BLE beacon. Home
+//! Assistant picks up these advertisements natively.
+//!
+//! | Object ID | Type      | Value                  |
+//! |-----------|-----------|------------------------|
+//! | `0x00`    | Packet ID | Given packet ID        |
+//! | `0x3D`    | Count     | UVA, rounded, `u16`    |
+//! | `0x3D`    | Count     | UVB, rounded, `u16`    |
+//! | `0x46`    | UV index  | UV index, 0.1 precision |
+//!
+//! Values outside of the ranges of the BTHome types are saturated.
+//!
+//! ```
+//! use veml6075::{bthome, Measurement};
+//!
+//! let m = Measurement::new(1000.0, 500.0, 1.23);
+//! let ad = bthome::encode(&m, 7);
+//! assert_eq!(
+//!     [14, 0x16, 0xD2, 0xFC, 0x40, 0x00, 7, 0x3D, 0xE8, 0x03, 0x3D, 0xF4, 0x01, 0x46, 12],
+//!     ad
+//! );
+//! ```
+
+use crate::Measurement;
+
+/// Encoded size of the AD structure in bytes.
+pub const AD_LEN: usize = 15;
+
+const SERVICE_DATA: u8 = 0x16;
+const UUID: u16 = 0xFCD2;
+const DEVICE_INFO: u8 = 0x40; // Version 2, not encrypted, regular interval
+const PACKET_ID: u8 = 0x00;
+const COUNT: u8 = 0x3D;
+const UV_INDEX: u8 = 0x46;
+
+/// Encode a calibrated measurement as BTHome service data AD structure.
+///
+/// The packet ID allows the receiver to discard duplicate advertisements.
+/// It should be changed whenever the measurement changes.
+pub fn encode(m: &Measurement, packet_id: u8) -> [u8; AD_LEN] {
+    let uva = ((m.uva + 0.5) as u16).to_le_bytes();
+    let uvb = ((m.uvb + 0.5) as u16).to_le_bytes();
+    let uv_index = (m.uv_index * 10.0 + 0.5) as u8;
+    let uuid = UUID.to_le_bytes();
+    [
+        (AD_LEN - 1) as u8,
+        SERVICE_DATA,
+        uuid[0],
+        uuid[1],
+        DEVICE_INFO,
+        PACKET_ID,
+        packet_id,
+        COUNT,
+        uva[0],
+        uva[1],
+        COUNT,
+        uvb[0],
+        uvb[1],
+        UV_INDEX,
+        uv_index,
+    ]
+}
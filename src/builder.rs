@@ -0,0 +1,98 @@
+//! Builder-style construction
+use crate::{
+    Calibration, ConfigSnapshot, DynamicSetting, Error, IntegrationTime, Mode, Veml6075,
+    DEVICE_ADDRESS,
+};
+use embedded_hal::blocking::i2c::Write;
+
+/// Driver builder
+///
+/// The configuration starts from [`ConfigSnapshot::default()`](struct.ConfigSnapshot.html#impl-Default)
+/// and is written to the device with a single register write on
+/// [`build()`](#method.build).
+///
+/// ```
+/// # use embedded_hal_mock::i2c::{Mock as I2cMock, Transaction as I2cTrans};
+/// use veml6075::{Calibration, DynamicSetting, IntegrationTime, Mode, Veml6075};
+///
+/// # let i2c = I2cMock::new(&[I2cTrans::write(0x10, vec![0, 0b0011_1010, 0])]);
+/// let sensor = Veml6075::builder()
+///     .integration_time(IntegrationTime::Ms400)
+///     .dynamic(DynamicSetting::High)
+///     .mode(Mode::ActiveForce)
+///     .calibration(Calibration::default())
+///     .build(i2c)
+///     .unwrap();
+/// # sensor.destroy().done();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Builder {
+    address: u8,
+    calibration: Calibration,
+    config: ConfigSnapshot,
+}
+
+impl Veml6075<()> {
+    /// Create a driver builder.
+    pub fn builder() -> Builder {
+        Builder {
+            address: DEVICE_ADDRESS,
+            calibration: Calibration::default(),
+            config: ConfigSnapshot::default(),
+        }
+    }
+}
+
+impl Builder {
+    /// Set the I²C address.
+    pub fn address(mut self, address: u8) -> Self {
+        self.address = address;
+        self
+    }
+
+    /// Set the calibration coefficients.
+    pub fn calibration(mut self, calibration: Calibration) -> Self {
+        self.calibration = calibration;
+        self
+    }
+
+    /// Set the integration time.
+    pub fn integration_time(mut self, it: IntegrationTime) -> Self {
+        self.config.integration_time = it;
+        self
+    }
+
+    /// Set the dynamic setting.
+    pub fn dynamic(mut self, ds: DynamicSetting) -> Self {
+        self.config.dynamic_setting = ds;
+        self
+    }
+
+    /// Set the operating mode.
+    pub fn mode(mut self, mode: Mode) -> Self {
+        self.config.mode = mode;
+        self
+    }
+
+    /// Set whether the sensor is enabled after building (default: enabled).
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.config.enabled = enabled;
+        self
+    }
+
+    /// Create the driver and write the configuration.
+    ///
+    /// Returns `Error::InvalidCalibration` if the calibration is not valid.
+    /// See [`Calibration::is_valid()`](struct.Calibration.html#method.is_valid).
+    pub fn build<I2C, E>(self, i2c: I2C) -> Result<Veml6075<I2C>, Error<E>>
+    where
+        I2C: Write<Error = E>,
+    {
+        if !self.calibration.is_valid() {
+            return Err(Error::InvalidCalibration);
+        }
+        let mut sensor = Veml6075::new_with_address(i2c, self.address, self.calibration);
+        sensor.restore(self.config)?;
+        Ok(sensor)
+    }
+}
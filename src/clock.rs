@@ -0,0 +1,86 @@
+//! Timestamped measurements
+use crate::{Error, Measurement, Veml6075};
+use embedded_hal::blocking::i2c::WriteRead;
+
+/// Monotonic clock providing timestamps in milliseconds.
+pub trait Clock {
+    /// Current time in milliseconds.
+    fn now_ms(&self) -> u64;
+}
+
+/// Calibrated measurement with the time at which it was read
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TimestampedMeasurement {
+    /// Timestamp in milliseconds
+    pub at: u64,
+    /// Calibrated measurement
+    pub measurement: Measurement,
+}
+
+impl<I2C, E> Veml6075<I2C>
+where
+    I2C: WriteRead<Error = E>,
+{
+    /// Read the calibrated measurement and timestamp it with the given clock.
+    pub fn read_timestamped<C: Clock>(
+        &mut self,
+        clock: &C,
+    ) -> Result<TimestampedMeasurement, Error<E>> {
+        let measurement = self.read()?;
+        Ok(TimestampedMeasurement {
+            at: clock.now_ms(),
+            measurement,
+        })
+    }
+
+    /// Return the last measurement read with this method if it is not older
+    /// than `max_age_ms`, otherwise read a new one.
+    ///
+    /// This is useful when several parts of an application need the current
+    /// measurement but the bus should only be accessed when necessary.
+    ///
+    /// Changing the calibration or the configuration discards the cached
+    /// measurement.
+    pub fn read_cached<C: Clock>(
+        &mut self,
+        clock: &C,
+        max_age_ms: u64,
+    ) -> Result<TimestampedMeasurement, Error<E>> {
+        if let Some(cached) = self.cached {
+            if clock.now_ms().saturating_sub(cached.at) <= max_age_ms {
+                return Ok(cached);
+            }
+        }
+        let m = self.read_timestamped(clock)?;
+        self.cached = Some(m);
+        Ok(m)
+    }
+}
+
+/// Wall clock providing milliseconds since the UNIX epoch.
+///
+/// Measurements read with this clock can be converted to `SystemTime`
+/// with [`TimestampedMeasurement::system_time()`](struct.TimestampedMeasurement.html#method.system_time).
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+#[cfg(feature = "std")]
+impl Clock for SystemClock {
+    fn now_ms(&self) -> u64 {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(feature = "std")]
+impl TimestampedMeasurement {
+    /// Timestamp as `SystemTime`, interpreting it as milliseconds since the UNIX epoch.
+    pub fn system_time(&self) -> std::time::SystemTime {
+        std::time::UNIX_EPOCH + std::time::Duration::from_millis(self.at)
+    }
+}
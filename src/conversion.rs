@@ -0,0 +1,78 @@
+//! Conversions between configuration enums and their register field values
+use crate::{DynamicSetting, IntegrationTime, Mode};
+use core::convert::TryFrom;
+
+impl From<IntegrationTime> for u8 {
+    /// Value of the `UV_IT` field (configuration register bits 6:4).
+    fn from(it: IntegrationTime) -> Self {
+        match it {
+            IntegrationTime::Ms50 => 0,
+            IntegrationTime::Ms100 => 1,
+            IntegrationTime::Ms200 => 2,
+            IntegrationTime::Ms400 => 3,
+            IntegrationTime::Ms800 => 4,
+        }
+    }
+}
+
+impl TryFrom<u8> for IntegrationTime {
+    type Error = ();
+
+    /// Parse the value of the `UV_IT` field (configuration register bits 6:4).
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(IntegrationTime::Ms50),
+            1 => Ok(IntegrationTime::Ms100),
+            2 => Ok(IntegrationTime::Ms200),
+            3 => Ok(IntegrationTime::Ms400),
+            4 => Ok(IntegrationTime::Ms800),
+            _ => Err(()),
+        }
+    }
+}
+
+impl From<DynamicSetting> for u8 {
+    /// Value of the `HD` field (configuration register bit 3).
+    fn from(ds: DynamicSetting) -> Self {
+        match ds {
+            DynamicSetting::Normal => 0,
+            DynamicSetting::High => 1,
+        }
+    }
+}
+
+impl TryFrom<u8> for DynamicSetting {
+    type Error = ();
+
+    /// Parse the value of the `HD` field (configuration register bit 3).
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(DynamicSetting::Normal),
+            1 => Ok(DynamicSetting::High),
+            _ => Err(()),
+        }
+    }
+}
+
+impl From<Mode> for u8 {
+    /// Value of the `UV_AF` field (configuration register bit 1).
+    fn from(mode: Mode) -> Self {
+        match mode {
+            Mode::Continuous => 0,
+            Mode::ActiveForce => 1,
+        }
+    }
+}
+
+impl TryFrom<u8> for Mode {
+    type Error = ();
+
+    /// Parse the value of the `UV_AF` field (configuration register bit 1).
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Mode::Continuous),
+            1 => Ok(Mode::ActiveForce),
+            _ => Err(()),
+        }
+    }
+}
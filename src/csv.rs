@@ -0,0 +1,23 @@
+//! CSV formatting
+use crate::Measurement;
+use core::fmt::{Result, Write};
+
+impl Measurement {
+    /// Write the CSV header line matching [`write_csv()`](#method.write_csv).
+    ///
+    /// This writes `timestamp,uva,uvb,uvi` followed by a newline.
+    pub fn write_csv_header<W: Write>(writer: &mut W) -> Result {
+        writer.write_str("timestamp,uva,uvb,uvi\n")
+    }
+
+    /// Write the measurement as a CSV line including a user-provided timestamp.
+    ///
+    /// For example: `1000,123.4,98.7,5.2` followed by a newline.
+    pub fn write_csv<W: Write>(&self, writer: &mut W, timestamp: u64) -> Result {
+        writeln!(
+            writer,
+            "{},{},{},{}",
+            timestamp, self.uva, self.uvb, self.uv_index
+        )
+    }
+}
@@ -1,40 +1,145 @@
 //! Device implementation
-use crate::{Calibration, DynamicSetting, Error, IntegrationTime, Measurement, Mode, Veml6075};
-use embedded_hal::blocking::i2c::{Write, WriteRead};
-
-struct Register;
-impl Register {
-    const CONFIG: u8 = 0x00;
-    const UVA: u8 = 0x07;
-    const UVB: u8 = 0x09;
-    const UVCOMP1: u8 = 0x0A;
-    const UVCOMP2: u8 = 0x0B;
-    const DEVICE_ID: u8 = 0x0C;
-}
-
-struct BitFlags;
-impl BitFlags {
-    const SHUTDOWN: u8 = 0b0000_0001;
-    const HD: u8 = 0b0000_1000;
-    const UV_TRIG: u8 = 0b0000_0100;
-    const UV_AF: u8 = 0b0000_0010;
-}
-
-const DEVICE_ADDRESS: u8 = 0x10;
+use crate::quality::QualityState;
+use crate::regs::{BitFlags, Register, DEVICE_ADDRESS, DEVICE_ID};
+use crate::{
+    BusStats, Calibration, ConfigSnapshot, Direction, DynamicSetting, Error, FullMeasurement,
+    IntegrationTime, Measurement, Mode, RawMeasurement, RetryPolicy, Veml6075, COVER_CALIBRATIONS,
+    SAMPLE_INTERVAL_MARGIN_PERCENT,
+};
+use core::{convert::TryFrom, time::Duration};
+use embedded_hal::blocking::{
+    delay::DelayMs,
+    i2c::{Write, WriteRead},
+};
 
 impl<I2C, E> Veml6075<I2C>
 where
     I2C: Write<Error = E>,
 {
     /// Create new instance of the Veml6075 device.
+    ///
+    /// The calibration is not validated. Use [`builder()`](#method.builder)
+    /// or [`set_calibration()`](#method.set_calibration) to reject invalid
+    /// coefficients.
     pub fn new(i2c: I2C, calibration: Calibration) -> Self {
+        Self::new_with_address(i2c, DEVICE_ADDRESS, calibration)
+    }
+
+    /// Create new instance of the Veml6075 device using an alternate I²C address.
+    ///
+    /// This is useful for devices behind address translators.
+    pub fn new_with_address(i2c: I2C, address: u8, calibration: Calibration) -> Self {
         Veml6075 {
             i2c,
+            address,
             config: 0x01, // shutdown
             calibration,
+            prepared: calibration.into(),
+            cached: None,
+            retry_policy: RetryPolicy::default(),
+            stats: BusStats::default(),
+            bus_observer: None,
+            quality: QualityState::default(),
+            double_read: false,
+            read_mismatches: 0,
+            settling: false,
+            calibrations: COVER_CALIBRATIONS,
         }
     }
 
+    /// Set the calibration coefficients.
+    ///
+    /// This discards the measurement cached by `read_cached()`.
+    ///
+    /// Returns `Error::InvalidCalibration` if the coefficients would yield
+    /// infinite or NaN measurements. See [`Calibration::is_valid()`](struct.Calibration.html#method.is_valid).
+    pub fn set_calibration(&mut self, calibration: Calibration) -> Result<(), Error<E>> {
+        if !calibration.is_valid() {
+            return Err(Error::InvalidCalibration);
+        }
+        self.calibration = calibration;
+        self.prepared = calibration.into();
+        self.cached = None;
+        Ok(())
+    }
+
+    /// Get the calibration coefficients.
+    pub fn calibration(&self) -> Calibration {
+        self.calibration
+    }
+
+    /// Set the named calibrations available to
+    /// [`set_calibration_by_name()`](#method.set_calibration_by_name).
+    ///
+    /// The default is [`COVER_CALIBRATIONS`](constant.COVER_CALIBRATIONS.html).
+    pub fn set_calibration_registry(&mut self, registry: &'static [(&'static str, Calibration)]) {
+        self.calibrations = registry;
+    }
+
+    /// Get the named calibrations available.
+    pub fn calibration_registry(&self) -> &'static [(&'static str, Calibration)] {
+        self.calibrations
+    }
+
+    /// Set the calibration coefficients registered with the given name.
+    ///
+    /// This is useful for devices with interchangeable optical covers.
+    pub fn set_calibration_by_name(&mut self, name: &str) -> Result<(), Error<E>> {
+        let calibration = self
+            .calibrations
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, c)| *c)
+            .ok_or(Error::UnknownCalibration)?;
+        self.set_calibration(calibration)
+    }
+
+    /// Set the I²C transaction retry policy.
+    pub fn set_retry_policy(&mut self, policy: RetryPolicy) {
+        self.retry_policy = policy;
+    }
+
+    /// Get the I²C bus error statistics.
+    ///
+    /// Steadily increasing error or retry counts point to a marginal bus,
+    /// for example weak pull-up resistors.
+    pub fn stats(&self) -> BusStats {
+        self.stats
+    }
+
+    /// Reset the I²C bus error statistics.
+    pub fn reset_stats(&mut self) {
+        self.stats = BusStats::default();
+    }
+
+    /// Read each data register twice and compare the values.
+    ///
+    /// This detects transient corruption on noisy buses, for example on
+    /// long cable runs. If the two values differ, the register is read a
+    /// third time and the value is accepted if it matches one of the
+    /// previous ones. Otherwise `Error::ReadMismatch` is returned. The
+    /// number of mismatches is available from
+    /// [`read_mismatches()`](#method.read_mismatches).
+    ///
+    /// This is disabled by default.
+    pub fn set_double_read(&mut self, enabled: bool) {
+        self.double_read = enabled;
+    }
+
+    /// Number of mismatching double reads detected.
+    pub fn read_mismatches(&self) -> u32 {
+        self.read_mismatches
+    }
+
+    /// Set a function to be called on every successful register access.
+    ///
+    /// The function receives the access direction, the register address
+    /// and the value written or read. This is useful to trace the
+    /// register accesses while debugging.
+    pub fn set_bus_observer(&mut self, observer: Option<fn(Direction, u8, u16)>) {
+        self.bus_observer = observer;
+    }
+
     /// Destroy driver instance, return I²C bus instance.
     pub fn destroy(self) -> I2C {
         self.i2c
@@ -43,7 +148,11 @@ where
     /// Enable the sensor.
     pub fn enable(&mut self) -> Result<(), Error<E>> {
         let config = self.config;
-        self.write_config(config & !BitFlags::SHUTDOWN)
+        self.write_config(config & !BitFlags::SHUTDOWN)?;
+        if config & BitFlags::SHUTDOWN != 0 {
+            self.quality.after_enable = true;
+        }
+        Ok(())
     }
 
     /// Disable the sensor (shutdown).
@@ -70,22 +179,13 @@ where
     pub fn trigger_measurement(&mut self) -> Result<(), Error<E>> {
         // this flag will automatically be set back to 0.
         let config = self.config | BitFlags::UV_TRIG;
-        self.i2c
-            .write(DEVICE_ADDRESS, &[Register::CONFIG, config, 0])
-            .map_err(Error::I2C)
+        self.write_register(Register::CONFIG, config)
     }
 
     /// Set the integration time.
     pub fn set_integration_time(&mut self, it: IntegrationTime) -> Result<(), Error<E>> {
-        let config = self.config & 0b1000_1111;
-        let config = match it {
-            IntegrationTime::Ms50 => config,
-            IntegrationTime::Ms100 => config | 1 << 4,
-            IntegrationTime::Ms200 => config | 2 << 4,
-            IntegrationTime::Ms400 => config | 3 << 4,
-            IntegrationTime::Ms800 => config | 4 << 4,
-        };
-        self.write_config(config)
+        let config = self.config & !BitFlags::UV_IT_MASK;
+        self.write_config(config | u8::from(it) << BitFlags::UV_IT_SHIFT)
     }
 
     /// Set the dynamic setting.
@@ -97,11 +197,26 @@ where
         self.write_config(config)
     }
 
-    fn write_config(&mut self, config: u8) -> Result<(), Error<E>> {
-        self.i2c
-            .write(DEVICE_ADDRESS, &[Register::CONFIG, config, 0])
-            .map_err(Error::I2C)?;
+    pub(crate) fn write_config(&mut self, config: u8) -> Result<(), Error<E>> {
+        self.write_register(Register::CONFIG, config)?;
+        debug!("Configuration written: {:#04x}", config);
         self.config = config;
+        self.settling = config & BitFlags::SHUTDOWN == 0;
+        self.cached = None;
+        Ok(())
+    }
+
+    fn write_register(&mut self, register: u8, value: u8) -> Result<(), Error<E>> {
+        let (i2c, address) = (&mut self.i2c, self.address);
+        self.retry_policy
+            .run(&mut self.stats, || {
+                i2c.write(address, &[register, value, 0])
+            })
+            .map_err(Error::I2C)?;
+        if let Some(observer) = self.bus_observer {
+            observer(Direction::Write, register, u16::from(value));
+        }
+        trace!("Register {:#04x} written: {:#04x}", register, value);
         Ok(())
     }
 }
@@ -111,41 +226,110 @@ where
     I2C: WriteRead<Error = E>,
 {
     /// Read the sensor data and calculate calibrated reading values.
+    ///
+    /// If the visible and IR compensation coefficients are all zero, the
+    /// UVcomp1 and UVcomp2 channels are not read.
     pub fn read(&mut self) -> Result<Measurement, Error<E>> {
-        let uva = self.read_uva_raw()?;
-        let uvb = self.read_uvb_raw()?;
-        let uvcomp1 = self.read_uvcomp1_raw()?;
-        let uvcomp2 = self.read_uvcomp2_raw()?;
-        let uva = f32::from(uva)
-            - (self.calibration.uva_visible * f32::from(uvcomp1))
-            - (self.calibration.uva_ir * f32::from(uvcomp2));
-        let uvb = f32::from(uvb)
-            - (self.calibration.uvb_visible * f32::from(uvcomp1))
-            - (self.calibration.uvb_ir * f32::from(uvcomp2));
-        let uv_index = (uva * self.calibration.uva_responsivity
-            + uvb * self.calibration.uvb_responsivity)
-            / 2.0;
-        Ok(Measurement { uva, uvb, uv_index })
+        let raw = if self.calibration.is_uncompensated() {
+            let (uva, uvb) = self.read_raw_pair()?;
+            RawMeasurement {
+                uva,
+                uvb,
+                uvcomp1: 0,
+                uvcomp2: 0,
+            }
+        } else {
+            self.read_raw()?
+        };
+        let m = self.prepared.compensate(&raw);
+        debug!(
+            "Measurement read: raw {:?}, UVA {}, UVB {}, UVI {}",
+            raw, m.uva, m.uvb, m.uv_index
+        );
+        Ok(m)
+    }
+
+    /// Read the raw, compensated and calibrated values of a single acquisition.
+    pub fn read_full(&mut self) -> Result<FullMeasurement, Error<E>> {
+        let raw = self.read_raw()?;
+        let (uva_compensated, uvb_compensated) = self.calibration.compensate_visible_ir(&raw);
+        Ok(FullMeasurement {
+            raw,
+            uva_compensated,
+            uvb_compensated,
+            measurement: self.prepared.compensate(&raw),
+        })
+    }
+
+    /// Read the raw UVA, UVB, UVcomp1 and UVcomp2 sensor data.
+    pub fn read_raw(&mut self) -> Result<RawMeasurement, Error<E>> {
+        let raw = RawMeasurement {
+            uva: self.read_uva_raw()?,
+            uvb: self.read_uvb_raw()?,
+            uvcomp1: self.read_uvcomp1_raw()?,
+            uvcomp2: self.read_uvcomp2_raw()?,
+        };
+        self.quality.after_enable = false;
+        Ok(raw)
+    }
+
+    /// Read the raw UVA and UVB sensor data without the compensation channels.
+    ///
+    /// Returns `(uva, uvb)`. The device does not support burst reads so
+    /// this performs two register reads.
+    pub fn read_raw_pair(&mut self) -> Result<(u16, u16), Error<E>> {
+        let pair = (self.read_uva_raw()?, self.read_uvb_raw()?);
+        self.quality.after_enable = false;
+        Ok(pair)
     }
 
     /// Read the raw UVA sensor data.
     pub fn read_uva_raw(&mut self) -> Result<u16, Error<E>> {
-        self.read_register(Register::UVA)
+        self.read_data_register(Register::UVA)
     }
 
     /// Read the raw UVB sensor data.
     pub fn read_uvb_raw(&mut self) -> Result<u16, Error<E>> {
-        self.read_register(Register::UVB)
+        self.read_data_register(Register::UVB)
     }
 
     /// Read the raw UVcomp1 sensor data.
     pub fn read_uvcomp1_raw(&mut self) -> Result<u16, Error<E>> {
-        self.read_register(Register::UVCOMP1)
+        self.read_data_register(Register::UVCOMP1)
     }
 
     /// Read the raw UVcomp2 sensor data.
     pub fn read_uvcomp2_raw(&mut self) -> Result<u16, Error<E>> {
-        self.read_register(Register::UVCOMP2)
+        self.read_data_register(Register::UVCOMP2)
+    }
+
+    /// Read the configuration from the device.
+    ///
+    /// This updates the configuration cached in the driver. This is useful
+    /// when the device was configured by a previous instance of the driver,
+    /// for example before a microcontroller reset.
+    pub fn load_config(&mut self) -> Result<(), Error<E>> {
+        let config = self.read_register(Register::CONFIG)?;
+        self.config = config as u8 & !BitFlags::UV_TRIG;
+        Ok(())
+    }
+
+    /// Verify that the configuration register matches the configuration set
+    /// through the driver.
+    ///
+    /// Returns `Error::ConfigMismatch` with both values otherwise, for example
+    /// after the device was reset by a power glitch or another bus master
+    /// changed the configuration. Use [`recover()`](#method.recover) to
+    /// write the configuration again.
+    pub fn verify_config(&mut self) -> Result<(), Error<E>> {
+        let actual = self.read_register(Register::CONFIG)? as u8 & !BitFlags::UV_TRIG;
+        if actual != self.config {
+            return Err(Error::ConfigMismatch {
+                expected: self.config,
+                actual,
+            });
+        }
+        Ok(())
     }
 
     /// Read the device ID
@@ -153,11 +337,184 @@ where
         self.read_register(Register::DEVICE_ID)
     }
 
-    fn read_register(&mut self, register: u8) -> Result<u16, Error<E>> {
+    fn read_data_register(&mut self, register: u8) -> Result<u16, Error<E>> {
+        let first = self.read_register(register)?;
+        if !self.double_read {
+            return Ok(first);
+        }
+        let second = self.read_register(register)?;
+        if first == second {
+            return Ok(first);
+        }
+        self.read_mismatches = self.read_mismatches.saturating_add(1);
+        debug!(
+            "Register {:#04x} read mismatch: {:#06x} != {:#06x}",
+            register, first, second
+        );
+        let third = self.read_register(register)?;
+        if third == first || third == second {
+            Ok(third)
+        } else {
+            Err(Error::ReadMismatch)
+        }
+    }
+
+    pub(crate) fn read_register(&mut self, register: u8) -> Result<u16, Error<E>> {
         let mut data = [0; 2];
-        self.i2c
-            .write_read(DEVICE_ADDRESS, &[register], &mut data)
+        let (i2c, address) = (&mut self.i2c, self.address);
+        self.retry_policy
+            .run(&mut self.stats, || {
+                i2c.write_read(address, &[register], &mut data)
+            })
             .map_err(Error::I2C)?;
-        Ok(u16::from(data[1]) << 8 | u16::from(data[0]))
+        let value = u16::from(data[1]) << 8 | u16::from(data[0]);
+        if let Some(observer) = self.bus_observer {
+            observer(Direction::Read, register, value);
+        }
+        trace!("Register {:#04x} read: {:#06x}", register, value);
+        Ok(value)
+    }
+}
+
+impl<I2C, E> Veml6075<I2C>
+where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+{
+    /// Perform the recommended startup sequence.
+    ///
+    /// This verifies the device ID, applies the default configuration (see
+    /// [`ConfigSnapshot::default()`](struct.ConfigSnapshot.html#impl-Default))
+    /// which enables the sensor, waits for the first integration period and
+    /// discards the first reading.
+    pub fn init<D: DelayMs<u16>>(&mut self, delay: &mut D) -> Result<(), Error<E>> {
+        if self.read_device_id()? != DEVICE_ID {
+            return Err(Error::InvalidDevice);
+        }
+        self.restore(ConfigSnapshot::default())?;
+        delay.delay_ms(self.integration_time_ms());
+        self.settling = false;
+        self.read_raw()?;
+        Ok(())
+    }
+
+    /// Read a calibrated measurement, triggering it first if needed.
+    ///
+    /// When on active force (one-shot) mode, this triggers a measurement
+    /// and waits for the integration time before reading it. When on
+    /// continuous mode, this reads the latest measurement right away.
+    pub fn read_oneshot<D: DelayMs<u16>>(
+        &mut self,
+        delay: &mut D,
+    ) -> Result<Measurement, Error<E>> {
+        if self.mode() == Mode::ActiveForce {
+            self.wait_integration(delay)?;
+        }
+        self.read()
+    }
+
+    /// Read a calibrated measurement once valid data is available.
+    ///
+    /// After enabling the sensor or changing its configuration, the first
+    /// valid data only appears after one integration period. If no reading
+    /// was taken through this method since then, this waits for the
+    /// integration time before reading. Otherwise it reads right away.
+    ///
+    /// This is intended for continuous mode. On active force (one-shot)
+    /// mode, use [`read_oneshot()`](#method.read_oneshot).
+    pub fn read_blocking<D: DelayMs<u16>>(
+        &mut self,
+        delay: &mut D,
+    ) -> Result<Measurement, Error<E>> {
+        if self.settling {
+            delay.delay_ms(self.integration_time_ms());
+            self.settling = false;
+        }
+        self.read()
+    }
+
+    /// Restore a known-good state after an I²C failure.
+    ///
+    /// This verifies the device ID and writes the cached configuration
+    /// to the device again.
+    pub fn recover(&mut self) -> Result<(), Error<E>> {
+        if self.read_device_id()? != DEVICE_ID {
+            return Err(Error::InvalidDevice);
+        }
+        let config = self.config;
+        self.write_config(config)?;
+        self.stats.recoveries = self.stats.recoveries.saturating_add(1);
+        Ok(())
+    }
+}
+
+impl<I2C> Veml6075<I2C> {
+    /// Get the configured integration time.
+    pub fn integration_time(&self) -> IntegrationTime {
+        IntegrationTime::try_from((self.config & BitFlags::UV_IT_MASK) >> BitFlags::UV_IT_SHIFT)
+            .unwrap_or(IntegrationTime::Ms800)
+    }
+
+    /// Get the configured dynamic setting.
+    pub fn dynamic_setting(&self) -> DynamicSetting {
+        if self.config & BitFlags::HD != 0 {
+            DynamicSetting::High
+        } else {
+            DynamicSetting::Normal
+        }
+    }
+
+    /// Get the minimum interval between samples yielding new conversions.
+    ///
+    /// This is the integration time plus a margin of
+    /// [`SAMPLE_INTERVAL_MARGIN_PERCENT`](constant.SAMPLE_INTERVAL_MARGIN_PERCENT.html)
+    /// for the tolerance of the internal oscillator. Reading more often
+    /// returns duplicate conversions.
+    pub fn min_sample_interval(&self) -> Duration {
+        let ms = u64::from(self.integration_time_ms());
+        Duration::from_millis(ms * (100 + SAMPLE_INTERVAL_MARGIN_PERCENT) / 100)
+    }
+
+    /// Get the maximum sample rate yielding new conversions in Hz.
+    ///
+    /// See [`min_sample_interval()`](#method.min_sample_interval).
+    pub fn max_sample_rate_hz(&self) -> f32 {
+        1000.0 / self.min_sample_interval().as_millis() as f32
+    }
+
+    pub(crate) fn integration_time_ms(&self) -> u16 {
+        50 << u8::from(self.integration_time())
+    }
+
+    pub(crate) fn mode(&self) -> Mode {
+        if self.config & BitFlags::UV_AF != 0 {
+            Mode::ActiveForce
+        } else {
+            Mode::Continuous
+        }
+    }
+}
+
+impl RetryPolicy {
+    fn run<T, E, F>(&self, stats: &mut BusStats, mut f: F) -> Result<T, E>
+    where
+        F: FnMut() -> Result<T, E>,
+    {
+        let mut retry = 0;
+        loop {
+            let result = f();
+            if result.is_err() {
+                stats.errors = stats.errors.saturating_add(1);
+            }
+            match result {
+                Err(_) if retry < self.retries => {
+                    retry += 1;
+                    stats.retries = stats.retries.saturating_add(1);
+                    if let Some(on_retry) = self.on_retry {
+                        on_retry(retry);
+                    }
+                }
+                result => return result,
+            }
+        }
     }
 }
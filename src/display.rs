@@ -0,0 +1,50 @@
+//! `Display` implementations
+use crate::{Error, Measurement, UvIndexClass};
+use core::fmt;
+
+impl fmt::Display for UvIndexClass {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            UvIndexClass::Low => "Low",
+            UvIndexClass::Moderate => "Moderate",
+            UvIndexClass::High => "High",
+            UvIndexClass::VeryHigh => "Very high",
+            UvIndexClass::Extreme => "Extreme",
+        };
+        f.write_str(name)
+    }
+}
+
+impl fmt::Display for Measurement {
+    /// Format as `UVA 123.4 UVB 98.7 UVI 5.2 (Moderate)`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "UVA {:.1} UVB {:.1} UVI {:.1} ({})",
+            self.uva,
+            self.uvb,
+            self.uv_index,
+            self.uv_index_class()
+        )
+    }
+}
+
+impl<E: fmt::Debug> fmt::Display for Error<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::I2C(e) => write!(f, "I2C bus error: {:?}", e),
+            Error::InvalidDevice => f.write_str("Invalid device"),
+            Error::InvalidCalibration => f.write_str("Invalid calibration"),
+            Error::UnknownCalibration => f.write_str("Unknown calibration"),
+            Error::ReadMismatch => f.write_str("Repeated register reads mismatch"),
+            Error::ConfigMismatch { expected, actual } => write!(
+                f,
+                "Configuration mismatch: expected {:#04x}, read {:#04x}",
+                expected, actual
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: fmt::Debug> std::error::Error for Error<E> {}
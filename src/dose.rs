@@ -0,0 +1,196 @@
+//! UV dosimetry.
+//!
+//! A UV index of 1 corresponds to an erythemally weighted irradiance of
+//! 25 mW/m². The doses are erythemally weighted and given in J/m².
+
+use crate::{Event, Thresholds};
+
+/// Erythemally weighted irradiance per UV index unit, in W/m².
+pub const IRRADIANCE_PER_UV_INDEX: f32 = 0.025;
+
+/// Erythemal dose received at a UV index during the given time, in J/m².
+pub fn erythemal_dose(uv_index: f32, elapsed_ms: u32) -> f32 {
+    uv_index.max(0.0) * IRRADIANCE_PER_UV_INDEX * elapsed_ms as f32 / 1000.0
+}
+
+/// Fitzpatrick skin type
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SkinType {
+    /// Type I: always burns, never tans
+    I,
+    /// Type II: usually burns, tans minimally
+    II,
+    /// Type III: sometimes burns, tans uniformly
+    III,
+    /// Type IV: burns minimally, always tans
+    IV,
+    /// Type V: very rarely burns, tans very easily
+    V,
+    /// Type VI: never burns
+    VI,
+}
+
+impl SkinType {
+    /// Typical minimal erythemal dose (MED) in J/m².
+    pub fn med(self) -> f32 {
+        match self {
+            SkinType::I => 200.0,
+            SkinType::II => 250.0,
+            SkinType::III => 300.0,
+            SkinType::IV => 450.0,
+            SkinType::V => 600.0,
+            SkinType::VI => 1000.0,
+        }
+    }
+}
+
+/// Sunburn alarm for a skin type and sun protection factor (SPF).
+///
+/// The guard accumulates the erythemal dose reduced by the SPF and reports
+/// when the fraction of the minimal erythemal dose (MED) reaches the
+/// warning levels, given in percent. By default, these are 50 %, 80 % and
+/// 100 %.
+///
+/// ```
+/// use veml6075::{dose::{SkinType, SunburnGuard}, Event};
+///
+/// let mut guard = SunburnGuard::new(SkinType::II, 15.0);
+/// // Called every minute with the current UV index.
+/// guard.update(8.0, 60_000, |event| {
+///     if let Event::CrossedUp(percent) = event {
+///         // Warn the user
+///     }
+/// });
+/// println!("{:.0} % of the MED received", guard.med_percent());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SunburnGuard {
+    skin_type: SkinType,
+    spf: f32,
+    dose: f32,
+    warnings: Thresholds,
+}
+
+impl SunburnGuard {
+    /// Create a new guard. Use an SPF of 1 for unprotected skin.
+    pub fn new(skin_type: SkinType, spf: f32) -> Self {
+        SunburnGuard {
+            skin_type,
+            spf: spf.max(1.0),
+            dose: 0.0,
+            warnings: Thresholds::new(&[50.0, 80.0, 100.0]),
+        }
+    }
+
+    /// Set the warning levels in percent of the MED in ascending order.
+    pub fn set_warning_levels(&mut self, percent: &[f32]) {
+        self.warnings = Thresholds::new(percent);
+        let current = self.med_percent();
+        self.warnings.update(current, |_| ());
+    }
+
+    /// Set the sun protection factor, for example after applying sunscreen.
+    pub fn set_spf(&mut self, spf: f32) {
+        self.spf = spf.max(1.0);
+    }
+
+    /// Accumulate the dose received at the UV index during the elapsed time.
+    ///
+    /// `f` is called with `Event::CrossedUp(percent)` for every warning level reached.
+    pub fn update<F: FnMut(Event)>(&mut self, uv_index: f32, elapsed_ms: u32, f: F) {
+        self.dose += erythemal_dose(uv_index, elapsed_ms) / self.spf;
+        let current = self.med_percent();
+        self.warnings.update(current, f);
+    }
+
+    /// Effective dose received after the SPF, in J/m².
+    pub fn dose(&self) -> f32 {
+        self.dose
+    }
+
+    /// Effective dose received in percent of the MED of the skin type.
+    pub fn med_percent(&self) -> f32 {
+        self.dose / self.skin_type.med() * 100.0
+    }
+
+    /// Reset the accumulated dose, for example after leaving the sun.
+    pub fn reset(&mut self) {
+        self.dose = 0.0;
+        self.warnings.update(0.0, |_| ());
+    }
+}
+
+/// Milliseconds in a day.
+pub const MS_PER_DAY: u64 = 24 * 60 * 60 * 1000;
+
+/// Daily erythemal dose tracker.
+///
+/// The day can be closed manually with [`reset_daily()`](#method.reset_daily)
+/// or automatically from the timestamps passed to
+/// [`update_at()`](#method.update_at).
+///
+/// ```
+/// use veml6075::dose::DailyDose;
+///
+/// let mut daily = DailyDose::new();
+/// daily.update(5.0, 60_000);
+/// daily.reset_daily(); // At midnight
+/// assert_eq!(Some(7.5), daily.yesterday());
+/// assert_eq!(0.0, daily.today());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct DailyDose {
+    today: f32,
+    yesterday: Option<f32>,
+    day: Option<u64>,
+}
+
+impl DailyDose {
+    /// Create a new tracker.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Accumulate the dose received at the UV index during the elapsed time.
+    pub fn update(&mut self, uv_index: f32, elapsed_ms: u32) {
+        self.today += erythemal_dose(uv_index, elapsed_ms);
+    }
+
+    /// Accumulate the dose and close the day if the timestamp is on a new day.
+    ///
+    /// The timestamp is in milliseconds since the UNIX epoch, for example from
+    /// [`SystemClock`](../struct.SystemClock.html), so days start at midnight
+    /// UTC. Add the UTC offset of the local time zone to start them at the
+    /// local midnight. If whole days were skipped, the dose of yesterday is 0.
+    pub fn update_at(&mut self, now_ms: u64, uv_index: f32, elapsed_ms: u32) {
+        let day = now_ms / MS_PER_DAY;
+        match self.day {
+            Some(previous) if day == previous + 1 => self.reset_daily(),
+            Some(previous) if day > previous => {
+                self.today = 0.0;
+                self.yesterday = Some(0.0);
+            }
+            _ => (),
+        }
+        self.day = Some(day);
+        self.update(uv_index, elapsed_ms);
+    }
+
+    /// Close the current day.
+    pub fn reset_daily(&mut self) {
+        self.yesterday = Some(self.today);
+        self.today = 0.0;
+    }
+
+    /// Dose received today, in J/m².
+    pub fn today(&self) -> f32 {
+        self.today
+    }
+
+    /// Total dose received yesterday, in J/m², if a day was closed already.
+    pub fn yesterday(&self) -> Option<f32> {
+        self.yesterday
+    }
+}
@@ -0,0 +1,75 @@
+//! Measurement filters
+
+/// Filtered estimate
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Estimate {
+    /// Estimated value
+    pub value: f32,
+    /// Variance of the estimate
+    pub variance: f32,
+}
+
+/// One-dimensional Kalman filter for smoothing the UV index.
+///
+/// The process noise models how fast the true value is expected to change
+/// between updates and the measurement noise how noisy each reading is.
+/// A lower process noise relative to the measurement noise results in
+/// smoother but slower estimates.
+///
+/// ```
+/// use veml6075::filter::Kalman;
+///
+/// let mut filter = Kalman::new(0.01, 0.5);
+/// let estimate = filter.update(5.2);
+/// println!("UVI: {:.1} ± {:.2}", estimate.value, estimate.variance);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Kalman {
+    process_noise: f32,
+    measurement_noise: f32,
+    estimate: Option<Estimate>,
+}
+
+impl Kalman {
+    /// Create a new filter with the given process and measurement noise variances.
+    pub fn new(process_noise: f32, measurement_noise: f32) -> Self {
+        Kalman {
+            process_noise,
+            measurement_noise,
+            estimate: None,
+        }
+    }
+
+    /// Update the filter with a new value and return the new estimate.
+    ///
+    /// The first value initializes the estimate.
+    pub fn update(&mut self, value: f32) -> Estimate {
+        let estimate = match self.estimate {
+            None => Estimate {
+                value,
+                variance: self.measurement_noise,
+            },
+            Some(previous) => {
+                let variance = previous.variance + self.process_noise;
+                let gain = variance / (variance + self.measurement_noise);
+                Estimate {
+                    value: previous.value + gain * (value - previous.value),
+                    variance: (1.0 - gain) * variance,
+                }
+            }
+        };
+        self.estimate = Some(estimate);
+        estimate
+    }
+
+    /// Current estimate, if any value has been processed.
+    pub fn estimate(&self) -> Option<Estimate> {
+        self.estimate
+    }
+
+    /// Discard the current estimate.
+    pub fn reset(&mut self) {
+        self.estimate = None;
+    }
+}
@@ -0,0 +1,36 @@
+//! Formatting helpers shared by the text exporters.
+
+use core::fmt::{Result, Write};
+
+/// Writer into a byte slice
+pub(crate) struct SliceWriter<'a> {
+    buffer: &'a mut [u8],
+    len: usize,
+}
+
+impl<'a> SliceWriter<'a> {
+    pub(crate) fn new(buffer: &'a mut [u8]) -> Self {
+        SliceWriter { buffer, len: 0 }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+
+    pub(crate) fn as_str(&self) -> &str {
+        // Only complete `str`s are written.
+        core::str::from_utf8(&self.buffer[..self.len]).unwrap_or("")
+    }
+}
+
+impl Write for SliceWriter<'_> {
+    fn write_str(&mut self, s: &str) -> Result {
+        let end = self.len + s.len();
+        if end > self.buffer.len() {
+            return Err(core::fmt::Error);
+        }
+        self.buffer[self.len..end].copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
@@ -0,0 +1,96 @@
+//! RAII shutdown guards
+use crate::{Error, Veml6075};
+use core::ops::{Deref, DerefMut};
+use embedded_hal::blocking::i2c::Write;
+
+/// Guard keeping the sensor enabled while in scope.
+///
+/// The sensor is shut down when the guard is dropped. This is best-effort:
+/// errors during shutdown are ignored. The driver can be used through the
+/// guard.
+///
+/// See [`enabled_scope()`](struct.Veml6075.html#method.enabled_scope).
+#[derive(Debug)]
+pub struct EnabledScope<'a, I2C: Write> {
+    sensor: &'a mut Veml6075<I2C>,
+}
+
+impl<I2C, E> Veml6075<I2C>
+where
+    I2C: Write<Error = E>,
+{
+    /// Enable the sensor and return a guard that shuts it down again on drop.
+    pub fn enabled_scope(&mut self) -> Result<EnabledScope<'_, I2C>, Error<E>> {
+        self.enable()?;
+        Ok(EnabledScope { sensor: self })
+    }
+}
+
+impl<'a, I2C: Write> Deref for EnabledScope<'a, I2C> {
+    type Target = Veml6075<I2C>;
+
+    fn deref(&self) -> &Self::Target {
+        self.sensor
+    }
+}
+
+impl<'a, I2C: Write> DerefMut for EnabledScope<'a, I2C> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.sensor
+    }
+}
+
+impl<'a, I2C: Write> Drop for EnabledScope<'a, I2C> {
+    fn drop(&mut self) {
+        self.sensor.disable().ok();
+    }
+}
+
+/// Driver wrapper shutting the sensor down when dropped or destroyed.
+///
+/// This is best-effort: errors during shutdown on drop are ignored.
+/// The driver can be used through the wrapper.
+#[derive(Debug)]
+pub struct ShutdownOnDrop<I2C: Write> {
+    sensor: Option<Veml6075<I2C>>,
+}
+
+impl<I2C: Write> ShutdownOnDrop<I2C> {
+    /// Wrap a driver instance.
+    pub fn new(sensor: Veml6075<I2C>) -> Self {
+        ShutdownOnDrop {
+            sensor: Some(sensor),
+        }
+    }
+
+    /// Shut the sensor down and return the I²C bus instance.
+    ///
+    /// The bus is returned even if the shutdown fails.
+    pub fn destroy(mut self) -> I2C {
+        let mut sensor = self.sensor.take().unwrap();
+        sensor.disable().ok();
+        sensor.destroy()
+    }
+}
+
+impl<I2C: Write> Deref for ShutdownOnDrop<I2C> {
+    type Target = Veml6075<I2C>;
+
+    fn deref(&self) -> &Self::Target {
+        self.sensor.as_ref().unwrap()
+    }
+}
+
+impl<I2C: Write> DerefMut for ShutdownOnDrop<I2C> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.sensor.as_mut().unwrap()
+    }
+}
+
+impl<I2C: Write> Drop for ShutdownOnDrop<I2C> {
+    fn drop(&mut self) {
+        if let Some(sensor) = self.sensor.as_mut() {
+            sensor.disable().ok();
+        }
+    }
+}
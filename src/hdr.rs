@@ -0,0 +1,123 @@
+//! High dynamic range measurements
+use crate::{DynamicSetting, Error, IntegrationTime, Measurement, Veml6075};
+use embedded_hal::blocking::{
+    delay::DelayMs,
+    i2c::{Write, WriteRead},
+};
+
+impl<I2C, E> Veml6075<I2C>
+where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+{
+    /// Read a measurement fusing a normal and a high dynamic setting reading.
+    ///
+    /// A reading is taken in each dynamic setting. Each channel uses the
+    /// normal dynamic reading unless it is saturated, in which case the high
+    /// dynamic reading scaled by its halved sensitivity is used instead. The
+    /// result is thus relative to the normal dynamic setting.
+    ///
+    /// The configured dynamic setting is restored afterwards. The sensor must
+    /// be enabled beforehand.
+    pub fn read_hdr<D: DelayMs<u16>>(&mut self, delay: &mut D) -> Result<Measurement, Error<E>> {
+        let snapshot = self.snapshot();
+        let result = self.read_hdr_steps(delay);
+        let restored = self.restore(snapshot);
+        let m = result?;
+        restored?;
+        Ok(m)
+    }
+
+    fn read_hdr_steps<D: DelayMs<u16>>(&mut self, delay: &mut D) -> Result<Measurement, Error<E>> {
+        self.set_dynamic_setting(DynamicSetting::Normal)?;
+        self.wait_integration(delay)?;
+        let normal = self.read_raw()?;
+        self.set_dynamic_setting(DynamicSetting::High)?;
+        self.wait_integration(delay)?;
+        let high = self.read_raw()?;
+        let fuse = |normal: u16, high: u16| {
+            if normal == 0xFFFF {
+                f32::from(high) * 2.0
+            } else {
+                f32::from(normal)
+            }
+        };
+        Ok(self.prepared.compensate_counts(
+            fuse(normal.uva, high.uva),
+            fuse(normal.uvb, high.uvb),
+            fuse(normal.uvcomp1, high.uvcomp1),
+            fuse(normal.uvcomp2, high.uvcomp2),
+        ))
+    }
+
+    /// Read a measurement fusing readings taken at several integration times.
+    ///
+    /// A reading is taken at each of the given integration times. Each
+    /// channel uses the reading with the longest integration time that is not
+    /// saturated, or the shortest one if all are. The counts are scaled to the
+    /// configured integration time, so the result is consistent with
+    /// [`read()`](#method.read) but has an extended dynamic range.
+    ///
+    /// The configured integration time is restored afterwards. The sensor
+    /// must be enabled beforehand. Without integration times, this reads at
+    /// the configured one.
+    pub fn read_bracketed<D: DelayMs<u16>>(
+        &mut self,
+        delay: &mut D,
+        integration_times: &[IntegrationTime],
+    ) -> Result<Measurement, Error<E>> {
+        let snapshot = self.snapshot();
+        let result = self.read_bracketed_steps(delay, integration_times);
+        let restored = self.restore(snapshot);
+        let m = result?;
+        restored?;
+        Ok(m)
+    }
+
+    fn read_bracketed_steps<D: DelayMs<u16>>(
+        &mut self,
+        delay: &mut D,
+        integration_times: &[IntegrationTime],
+    ) -> Result<Measurement, Error<E>> {
+        let reference = f32::from(self.integration_time_ms());
+        let current = [self.integration_time()];
+        let integration_times = if integration_times.is_empty() {
+            &current[..]
+        } else {
+            integration_times
+        };
+        // Selected (integration time in ms, counts) per channel
+        let mut selected: [Option<(u16, u16)>; 4] = [None; 4];
+        for &it in integration_times {
+            self.set_integration_time(it)?;
+            self.wait_integration(delay)?;
+            let raw = self.read_raw()?;
+            let ms = self.integration_time_ms();
+            let channels = [raw.uva, raw.uvb, raw.uvcomp1, raw.uvcomp2];
+            for (selected, &counts) in selected.iter_mut().zip(channels.iter()) {
+                let better = match *selected {
+                    None => true,
+                    Some((old_ms, old_counts)) => match (old_counts == 0xFFFF, counts == 0xFFFF) {
+                        (true, false) => true,
+                        (false, true) => false,
+                        (false, false) => ms > old_ms,
+                        (true, true) => ms < old_ms,
+                    },
+                };
+                if better {
+                    *selected = Some((ms, counts));
+                }
+            }
+        }
+        let scaled = |selected: Option<(u16, u16)>| {
+            selected.map_or(0.0, |(ms, counts)| {
+                f32::from(counts) * reference / f32::from(ms)
+            })
+        };
+        Ok(self.prepared.compensate_counts(
+            scaled(selected[0]),
+            scaled(selected[1]),
+            scaled(selected[2]),
+            scaled(selected[3]),
+        ))
+    }
+}
@@ -0,0 +1,92 @@
+//! Integration time headroom check
+use crate::{DynamicSetting, IntegrationTime, RawMeasurement, Veml6075};
+
+const FULL_SCALE: f32 = 65535.0;
+
+/// Headroom check result and configuration recommendation
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Headroom {
+    /// Highest channel count as a fraction of full scale
+    pub full_scale_fraction: f32,
+    /// Whether a channel was at full scale, so the actual signal is unknown
+    pub saturated: bool,
+    /// Recommended integration time
+    pub integration_time: IntegrationTime,
+    /// Recommended dynamic setting
+    pub dynamic_setting: DynamicSetting,
+}
+
+/// Relative sensitivity, in units of 50 ms at high dynamic setting.
+fn sensitivity(it: IntegrationTime, ds: DynamicSetting) -> u32 {
+    let factor = match ds {
+        DynamicSetting::Normal => 2,
+        DynamicSetting::High => 1,
+    };
+    factor << u8::from(it)
+}
+
+/// Check how close raw counts are to full scale and recommend a configuration.
+///
+/// The counts must have been read with the given integration time and
+/// dynamic setting. The recommendation is the most sensitive configuration,
+/// preferring longer integration times, whose highest expected channel count
+/// stays below full scale by the given headroom fraction, for example 0.2
+/// for 20 %. If no configuration fits, the least sensitive one is recommended.
+///
+/// The counts of a saturated reading are unknown so the recommendation is
+/// only guaranteed to be less sensitive. Check again with it.
+pub fn check_headroom(
+    raw: &RawMeasurement,
+    it: IntegrationTime,
+    ds: DynamicSetting,
+    headroom: f32,
+) -> Headroom {
+    const ITS: [IntegrationTime; 5] = [
+        IntegrationTime::Ms50,
+        IntegrationTime::Ms100,
+        IntegrationTime::Ms200,
+        IntegrationTime::Ms400,
+        IntegrationTime::Ms800,
+    ];
+    let channels = [raw.uva, raw.uvb, raw.uvcomp1, raw.uvcomp2];
+    let peak = f32::from(*channels.iter().max().unwrap_or(&0));
+    let saturated = channels.contains(&0xFFFF);
+    let limit = FULL_SCALE * (1.0 - headroom);
+    let current = sensitivity(it, ds) as f32;
+    let mut best = (IntegrationTime::Ms50, DynamicSetting::High);
+    for &candidate_it in ITS.iter() {
+        for &candidate_ds in [DynamicSetting::High, DynamicSetting::Normal].iter() {
+            let s = sensitivity(candidate_it, candidate_ds);
+            let expected = peak * s as f32 / current;
+            let fits = if saturated {
+                (s as f32) < current
+            } else {
+                expected <= limit
+            };
+            if fits && s >= sensitivity(best.0, best.1) {
+                best = (candidate_it, candidate_ds);
+            }
+        }
+    }
+    Headroom {
+        full_scale_fraction: peak / FULL_SCALE,
+        saturated,
+        integration_time: best.0,
+        dynamic_setting: best.1,
+    }
+}
+
+impl<I2C> Veml6075<I2C> {
+    /// Check the headroom of raw counts read with the current configuration.
+    ///
+    /// See [`check_headroom()`](fn.check_headroom.html).
+    pub fn check_headroom(&self, raw: &RawMeasurement, headroom: f32) -> Headroom {
+        check_headroom(
+            raw,
+            self.integration_time(),
+            self.dynamic_setting(),
+            headroom,
+        )
+    }
+}
@@ -0,0 +1,171 @@
+//! Hardware-in-the-loop acceptance test.
+//!
+//! [`run()`](fn.run.html) checks the device ID and then measures every
+//! integration time and dynamic setting combination in active force
+//! (one-shot) mode, verifying that all channels are within the expected
+//! ranges. This can be used to test assembled boards under a known light
+//! source.
+
+use crate::regs::DEVICE_ID;
+use crate::{DynamicSetting, Error, IntegrationTime, Mode, RawMeasurement, Veml6075};
+use embedded_hal::blocking::{
+    delay::DelayMs,
+    i2c::{Write, WriteRead},
+};
+
+/// Number of steps in the test sequence
+pub const STEP_COUNT: usize = 10;
+
+/// Index of the least sensitive step (50 ms and high dynamic setting)
+const LEAST_SENSITIVE_STEP: usize = 1;
+
+/// Expected channel count ranges.
+///
+/// The counts are normalized to 100 ms integration time and normal dynamic
+/// setting before the comparison.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Limits {
+    /// Minimum normalized counts (inclusive)
+    pub min: RawMeasurement,
+    /// Maximum normalized counts (inclusive)
+    pub max: RawMeasurement,
+}
+
+/// Result of a step of the test sequence
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct StepResult {
+    /// Integration time of the step
+    pub integration_time: IntegrationTime,
+    /// Dynamic setting of the step
+    pub dynamic_setting: DynamicSetting,
+    /// Raw counts read
+    pub raw: RawMeasurement,
+    /// Whether any channel was at full scale. Saturated steps are not
+    /// checked against the limits, except the least sensitive step (50 ms
+    /// and high dynamic setting), which fails if saturated. This detects
+    /// channels stuck at full scale.
+    pub saturated: bool,
+    /// Whether the step passed
+    pub passed: bool,
+}
+
+/// Acceptance test report
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Report {
+    /// Device ID read
+    pub device_id: u16,
+    /// Results of the steps, or `None` if the device ID was wrong
+    pub steps: Option<[StepResult; STEP_COUNT]>,
+}
+
+impl Report {
+    /// Whether the device ID was correct and all steps passed.
+    pub fn passed(&self) -> bool {
+        match &self.steps {
+            Some(steps) => steps.iter().all(|step| step.passed),
+            None => false,
+        }
+    }
+}
+
+/// Run the acceptance test sequence.
+///
+/// The sensor configuration is restored afterwards. I²C errors abort the
+/// test and are returned.
+pub fn run<I2C, E, D>(
+    sensor: &mut Veml6075<I2C>,
+    delay: &mut D,
+    limits: &Limits,
+) -> Result<Report, Error<E>>
+where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+    D: DelayMs<u16>,
+{
+    let device_id = sensor.read_device_id()?;
+    if device_id != DEVICE_ID {
+        return Ok(Report {
+            device_id,
+            steps: None,
+        });
+    }
+    let config = sensor.config;
+    let result = run_steps(sensor, delay, limits);
+    let restored = sensor.write_config(config);
+    let steps = result?;
+    restored?;
+    Ok(Report {
+        device_id,
+        steps: Some(steps),
+    })
+}
+
+fn run_steps<I2C, E, D>(
+    sensor: &mut Veml6075<I2C>,
+    delay: &mut D,
+    limits: &Limits,
+) -> Result<[StepResult; STEP_COUNT], Error<E>>
+where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+    D: DelayMs<u16>,
+{
+    const ITS: [IntegrationTime; 5] = [
+        IntegrationTime::Ms50,
+        IntegrationTime::Ms100,
+        IntegrationTime::Ms200,
+        IntegrationTime::Ms400,
+        IntegrationTime::Ms800,
+    ];
+    const DSS: [DynamicSetting; 2] = [DynamicSetting::Normal, DynamicSetting::High];
+    sensor.set_mode(Mode::ActiveForce)?;
+    sensor.enable()?;
+    let mut steps = [StepResult {
+        integration_time: IntegrationTime::Ms50,
+        dynamic_setting: DynamicSetting::Normal,
+        raw: RawMeasurement {
+            uva: 0,
+            uvb: 0,
+            uvcomp1: 0,
+            uvcomp2: 0,
+        },
+        saturated: false,
+        passed: false,
+    }; STEP_COUNT];
+    for (i, step) in steps.iter_mut().enumerate() {
+        let (it, ds) = (ITS[i / 2], DSS[i % 2]);
+        sensor.set_integration_time(it)?;
+        sensor.set_dynamic_setting(ds)?;
+        sensor.trigger_measurement()?;
+        delay.delay_ms(sensor.integration_time_ms());
+        let raw = sensor.read_raw()?;
+        let channels = [raw.uva, raw.uvb, raw.uvcomp1, raw.uvcomp2];
+        let saturated = channels.contains(&0xFFFF);
+        let factor = match ds {
+            DynamicSetting::Normal => 1,
+            DynamicSetting::High => 2,
+        };
+        let normalize = |counts: u16| u32::from(counts) * 100 * factor / (50 << u8::from(it));
+        let in_range = |counts: u16, min: u16, max: u16| {
+            let counts = normalize(counts);
+            counts >= u32::from(min) && counts <= u32::from(max)
+        };
+        let (min, max) = (&limits.min, &limits.max);
+        *step = StepResult {
+            integration_time: it,
+            dynamic_setting: ds,
+            raw,
+            saturated,
+            passed: if saturated {
+                i != LEAST_SENSITIVE_STEP
+            } else {
+                in_range(raw.uva, min.uva, max.uva)
+                    && in_range(raw.uvb, min.uvb, max.uvb)
+                    && in_range(raw.uvcomp1, min.uvcomp1, max.uvcomp1)
+                    && in_range(raw.uvcomp2, min.uvcomp2, max.uvcomp2)
+            },
+        };
+    }
+    Ok(steps)
+}
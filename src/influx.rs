@@ -0,0 +1,53 @@
+//! InfluxDB line protocol
+use crate::{fmt_util::SliceWriter, Measurement};
+use core::fmt::{Result, Write};
+
+impl Measurement {
+    /// Write the measurement as an InfluxDB line protocol line into the provided buffer.
+    ///
+    /// The line contains the `uva`, `uvb` and `uvi` fields and the given
+    /// tags. The optional timestamp must use the precision configured on the
+    /// receiving end (nanoseconds by default). Spaces, commas and equal signs
+    /// in the measurement name and tags are escaped.
+    ///
+    /// For example: `uv,location=garden uva=123.4,uvb=98.7,uvi=5.2 1700000000000000000`
+    /// followed by a newline.
+    ///
+    /// Returns the number of bytes written or an error if the buffer is too small.
+    pub fn to_line_protocol(
+        &self,
+        measurement_name: &str,
+        tags: &[(&str, &str)],
+        timestamp: Option<u64>,
+        buffer: &mut [u8],
+    ) -> core::result::Result<usize, core::fmt::Error> {
+        let mut writer = SliceWriter::new(buffer);
+        write_escaped(&mut writer, measurement_name, false)?;
+        for (key, value) in tags {
+            writer.write_char(',')?;
+            write_escaped(&mut writer, key, true)?;
+            writer.write_char('=')?;
+            write_escaped(&mut writer, value, true)?;
+        }
+        write!(
+            writer,
+            " uva={},uvb={},uvi={}",
+            self.uva, self.uvb, self.uv_index
+        )?;
+        if let Some(timestamp) = timestamp {
+            write!(writer, " {}", timestamp)?;
+        }
+        writer.write_char('\n')?;
+        Ok(writer.len())
+    }
+}
+
+fn write_escaped<W: Write>(writer: &mut W, s: &str, is_tag: bool) -> Result {
+    for c in s.chars() {
+        if c == ' ' || c == ',' || (is_tag && c == '=') {
+            writer.write_char('\\')?;
+        }
+        writer.write_char(c)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,71 @@
+//! Blocking measurement iterator
+use crate::{Error, Measurement, Mode, Veml6075};
+use embedded_hal::blocking::{
+    delay::DelayMs,
+    i2c::{Write, WriteRead},
+};
+
+/// Iterator over calibrated measurements paced at the integration time.
+///
+/// See [`iter_measurements()`](struct.Veml6075.html#method.iter_measurements).
+#[derive(Debug)]
+pub struct Measurements<'a, I2C, D> {
+    sensor: &'a mut Veml6075<I2C>,
+    delay: &'a mut D,
+}
+
+impl<I2C, E> Veml6075<I2C>
+where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+{
+    /// Iterate over calibrated measurements.
+    ///
+    /// Each iteration waits for the configured integration time before
+    /// reading the measurement. When on active force (one-shot) mode,
+    /// a measurement is triggered before waiting.
+    ///
+    /// The iterator never ends. The sensor must be enabled beforehand.
+    pub fn iter_measurements<'a, D: DelayMs<u16>>(
+        &'a mut self,
+        delay: &'a mut D,
+    ) -> Measurements<'a, I2C, D> {
+        Measurements {
+            sensor: self,
+            delay,
+        }
+    }
+
+    /// Trigger a measurement if on active force mode, wait the integration
+    /// time and read the measurement.
+    pub(crate) fn wait_and_read<D: DelayMs<u16>>(
+        &mut self,
+        delay: &mut D,
+    ) -> Result<Measurement, Error<E>> {
+        self.wait_integration(delay)?;
+        self.read()
+    }
+
+    /// Trigger a measurement if on active force mode and wait the integration time.
+    ///
+    /// Afterwards, a complete conversion can be read, for example with
+    /// [`read_full()`](#method.read_full).
+    pub fn wait_integration<D: DelayMs<u16>>(&mut self, delay: &mut D) -> Result<(), Error<E>> {
+        if self.mode() == Mode::ActiveForce {
+            self.trigger_measurement()?;
+        }
+        delay.delay_ms(self.integration_time_ms());
+        Ok(())
+    }
+}
+
+impl<'a, I2C, E, D> Iterator for Measurements<'a, I2C, D>
+where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+    D: DelayMs<u16>,
+{
+    type Item = Result<Measurement, Error<E>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.sensor.wait_and_read(self.delay))
+    }
+}
@@ -0,0 +1,13 @@
+//! JSON serialization
+use crate::Measurement;
+
+impl Measurement {
+    /// Serialize the measurement as JSON into the provided buffer.
+    ///
+    /// For example: `{"uva":123.4,"uvb":98.7,"uv_index":5.2}`.
+    ///
+    /// Returns the number of bytes written.
+    pub fn write_json(&self, buffer: &mut [u8]) -> Result<usize, serde_json_core::ser::Error> {
+        serde_json_core::to_slice(self, buffer)
+    }
+}
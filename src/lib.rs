@@ -5,24 +5,166 @@
 //!
 //! This driver allows you to:
 //! - Enable/disable the sensor. See: [`enable()`].
+//! - Run the recommended startup sequence. See: [`init()`].
+//! - Keep the sensor enabled only within a scope. See: [`enabled_scope()`].
+//! - Shut the sensor down when the driver is dropped. See: [`ShutdownOnDrop`].
 //! - Read calibrated UVA, UVB and UV index measurement. See: [`read()`].
+//! - Wait for the first valid data after a configuration change. See: [`read_blocking()`].
+//! - Read timestamped measurements using a user-provided clock. See: [`read_timestamped()`].
+//! - Reuse a recent measurement instead of reading again. See: [`read_cached()`].
+//! - Change the calibration coefficients at runtime. See: [`set_calibration()`].
+//! - Trim the UVA and UVB gain per unit. See: [`Calibration::with_gain_trim()`].
+//! - Store the calibration as a checksummed byte blob. See: [`Calibration::to_bytes()`].
+//! - Select calibration presets for common cover materials. See: [`Calibration::for_cover()`].
+//! - Switch between named calibrations at runtime. See: [`set_calibration_by_name()`].
+//! - Calibrate against a golden reference unit. See: [`CalibrationWizard`].
+//! - Classify the UV index into WHO exposure categories. See: [`uv_index_class()`].
+//! - Warn before sunburn for a skin type and SPF. See: [`dose::SunburnGuard`].
+//! - Track today's and yesterday's UV dose. See: [`dose::DailyDose`].
+//! - Detect whether the UV index is rising or falling. See: [`trend`].
+//! - Add, subtract and average measurements. See: [`Measurement::mean()`].
 //! - Read raw measurement. See: [`read_uva_raw()`].
+//! - Read all raw channels at once. See: [`read_raw()`].
+//! - Average several raw readings in integers. See: [`Oversampler`].
+//! - Read raw, compensated and calibrated values together. See: [`read_full()`].
+//! - Read raw UVA and UVB only. See: [`read_raw_pair()`].
+//! - Read measurements with quality flags. See: [`read_flagged()`].
+//! - Estimate the noise of each channel. See: [`estimate_noise()`].
+//! - Compute the signal-to-noise ratio of a reading. See: [`NoiseEstimate::snr()`].
 //! - Set integration time. See: [`set_integration_time()`].
+//! - Get the configured integration time. See: [`integration_time()`].
+//! - Get the maximum sample rate for the configuration. See: [`max_sample_rate_hz()`].
+//! - Check the headroom to full scale and get a recommended configuration. See: [`check_headroom()`].
+//! - Adjust the integration time automatically to keep the counts in a target band. See: [`AutoExposure`].
+//! - Extend the range by fusing normal and high dynamic readings. See: [`read_hdr()`].
+//! - Extend the range by fusing readings at several integration times. See: [`read_bracketed()`].
 //! - Set dynamic setting. See: [`set_dynamic_setting()`].
 //! - Change operating mode. See: [`set_mode()`].
 //! - Trigger measurement when on active force mode. See: [`trigger_measurement()`].
+//! - Trigger, wait and read in a single call when on active force mode. See: [`read_oneshot()`].
+//! - Split the driver into configuration and data reading halves. See: [`split()`].
+//! - Iterate over measurements paced at the integration time. See: [`iter_measurements()`].
+//! - Sample with automatic timing in both operating modes. See: [`Sampler`].
+//! - Schedule periodic measurements from a timer without blocking. See: [`Scheduler`].
+//! - Get notified of every new measurement. See: [`set_on_measurement()`].
+//! - Get events when the UV index crosses configured levels. See: [`Thresholds`].
+//! - Sample from a timer interrupt with a non-blocking state machine. See: [`Poller`].
+//! - Fan measurements out to several consumers on schedule. See: [`Pump`].
+//! - Log measurements to CSV or JSON lines files on Linux. See: [`logger`].
+//! - Duty-cycle the sensor to minimize power consumption. See: [`LowPowerSampler`].
+//! - Estimate the power consumption of a configuration. See: [`power::estimate()`].
+//! - Smooth the UV index with a Kalman filter. See: [`filter::Kalman`].
 //! - Read the device id. See: [`read_device_id()`].
+//! - Use an alternate I²C address. See: [`new_with_address()`].
+//! - Construct and configure the driver in one expression. See: [`builder()`].
+//! - Open the sensor from a Linux I²C device path. See: [`open()`].
+//! - Use the driver without giving up the bus ownership. See: [`new_borrowed()`].
+//! - Load the configuration from the device. See: [`load_config()`].
+//! - Capture and restore the configuration in one write. See: [`snapshot()`].
+//! - Preserve the driver state across a deep sleep. See: [`suspend()`].
+//! - Detect sensor resets and foreign configuration changes. See: [`verify_config()`].
+//! - Retry failed I²C transactions. See: [`set_retry_policy()`].
+//! - Count I²C errors, retries and recoveries. See: [`stats()`].
+//! - Detect corrupted reads on noisy buses. See: [`set_double_read()`].
+//! - Perform register reads as single I²C transactions. See: [`TransactionI2c`].
+//! - Restore a known-good state after an I²C failure. See: [`recover()`].
+//! - Run an acceptance test on assembled boards. See: [`hil::run()`].
+//! - Run a short power-on self-test. See: [`self_test()`].
+//! - Trace register accesses. See: [`set_bus_observer()`].
+//! - Access the register map directly. See: [`regs`].
+//! - Use several sensors behind a PCA954x I²C multiplexer. See: [`MuxedVeml6075`].
+//! - Manage several sensors at once. See: [`Veml6075Array`].
+//! - Write code generic over the UV sensor. See: [`UvSensor`].
+//! - Encode measurements in a compact binary format. See: [`wire`].
+//! - Encode measurements as Cayenne LPP for LoRaWAN. See: [`lpp`].
+//! - Broadcast measurements as BTHome BLE advertisements. See: [`bthome`].
+//! - Format measurements as CSV lines. See: [`write_csv()`].
+//! - Expose measurements as Prometheus gauges. See: [`write_prometheus()`].
+//! - Format measurements as SenML JSON packs. See: [`write_senml()`].
+//! - Format measurements as InfluxDB line protocol. See: [`to_line_protocol()`].
+//! - Publish measurements to Home Assistant over MQTT. See: [`mqtt`].
 //!
 //! [Introductory blog post](https://blog.eldruin.com/veml6075-uva-uvb-uv-index-light-sensor-driver-in-rust/)
 //!
 //! [`enable()`]: struct.Veml6075.html#method.enable
+//! [`init()`]: struct.Veml6075.html#method.init
+//! [`enabled_scope()`]: struct.Veml6075.html#method.enabled_scope
+//! [`ShutdownOnDrop`]: struct.ShutdownOnDrop.html
 //! [`read()`]: struct.Veml6075.html#method.read
+//! [`read_blocking()`]: struct.Veml6075.html#method.read_blocking
+//! [`read_timestamped()`]: struct.Veml6075.html#method.read_timestamped
+//! [`read_cached()`]: struct.Veml6075.html#method.read_cached
+//! [`set_calibration()`]: struct.Veml6075.html#method.set_calibration
+//! [`Calibration::with_gain_trim()`]: struct.Calibration.html#method.with_gain_trim
+//! [`Calibration::to_bytes()`]: struct.Calibration.html#method.to_bytes
+//! [`Calibration::for_cover()`]: struct.Calibration.html#method.for_cover
+//! [`set_calibration_by_name()`]: struct.Veml6075.html#method.set_calibration_by_name
+//! [`CalibrationWizard`]: struct.CalibrationWizard.html
+//! [`uv_index_class()`]: struct.Measurement.html#method.uv_index_class
+//! [`dose::SunburnGuard`]: dose/struct.SunburnGuard.html
+//! [`dose::DailyDose`]: dose/struct.DailyDose.html
+//! [`trend`]: trend/index.html
+//! [`Measurement::mean()`]: struct.Measurement.html#method.mean
 //! [`read_uva_raw()`]: struct.Veml6075.html#method.read_uva_raw
+//! [`read_raw()`]: struct.Veml6075.html#method.read_raw
+//! [`Oversampler`]: struct.Oversampler.html
+//! [`read_full()`]: struct.Veml6075.html#method.read_full
+//! [`read_raw_pair()`]: struct.Veml6075.html#method.read_raw_pair
+//! [`read_flagged()`]: struct.Veml6075.html#method.read_flagged
+//! [`estimate_noise()`]: struct.Veml6075.html#method.estimate_noise
+//! [`NoiseEstimate::snr()`]: struct.NoiseEstimate.html#method.snr
 //! [`set_integration_time()`]: struct.Veml6075.html#method.set_integration_time
+//! [`integration_time()`]: struct.Veml6075.html#method.integration_time
+//! [`max_sample_rate_hz()`]: struct.Veml6075.html#method.max_sample_rate_hz
+//! [`check_headroom()`]: fn.check_headroom.html
+//! [`AutoExposure`]: struct.AutoExposure.html
+//! [`read_hdr()`]: struct.Veml6075.html#method.read_hdr
+//! [`read_bracketed()`]: struct.Veml6075.html#method.read_bracketed
 //! [`set_dynamic_setting()`]: struct.Veml6075.html#method.set_dynamic_setting
 //! [`set_mode()`]: struct.Veml6075.html#method.set_mode
 //! [`trigger_measurement()`]: struct.Veml6075.html#method.trigger_measurement
+//! [`read_oneshot()`]: struct.Veml6075.html#method.read_oneshot
+//! [`split()`]: struct.Veml6075.html#method.split
+//! [`iter_measurements()`]: struct.Veml6075.html#method.iter_measurements
+//! [`Sampler`]: struct.Sampler.html
+//! [`Scheduler`]: struct.Scheduler.html
+//! [`set_on_measurement()`]: struct.Sampler.html#method.set_on_measurement
+//! [`Thresholds`]: struct.Thresholds.html
+//! [`Poller`]: struct.Poller.html
+//! [`Pump`]: struct.Pump.html
+//! [`logger`]: logger/index.html
+//! [`LowPowerSampler`]: struct.LowPowerSampler.html
+//! [`power::estimate()`]: power/fn.estimate.html
+//! [`filter::Kalman`]: filter/struct.Kalman.html
 //! [`read_device_id()`]: struct.Veml6075.html#method.read_device_id
+//! [`new_with_address()`]: struct.Veml6075.html#method.new_with_address
+//! [`builder()`]: struct.Veml6075.html#method.builder
+//! [`open()`]: struct.Veml6075.html#method.open
+//! [`new_borrowed()`]: struct.Veml6075.html#method.new_borrowed
+//! [`load_config()`]: struct.Veml6075.html#method.load_config
+//! [`snapshot()`]: struct.Veml6075.html#method.snapshot
+//! [`suspend()`]: struct.Veml6075.html#method.suspend
+//! [`verify_config()`]: struct.Veml6075.html#method.verify_config
+//! [`set_retry_policy()`]: struct.Veml6075.html#method.set_retry_policy
+//! [`stats()`]: struct.Veml6075.html#method.stats
+//! [`set_double_read()`]: struct.Veml6075.html#method.set_double_read
+//! [`TransactionI2c`]: struct.TransactionI2c.html
+//! [`recover()`]: struct.Veml6075.html#method.recover
+//! [`hil::run()`]: hil/fn.run.html
+//! [`self_test()`]: struct.Veml6075.html#method.self_test
+//! [`set_bus_observer()`]: struct.Veml6075.html#method.set_bus_observer
+//! [`regs`]: regs/index.html
+//! [`MuxedVeml6075`]: type.MuxedVeml6075.html
+//! [`Veml6075Array`]: struct.Veml6075Array.html
+//! [`UvSensor`]: trait.UvSensor.html
+//! [`wire`]: wire/index.html
+//! [`lpp`]: lpp/index.html
+//! [`bthome`]: bthome/index.html
+//! [`write_csv()`]: struct.Measurement.html#method.write_csv
+//! [`write_prometheus()`]: struct.Measurement.html#method.write_prometheus
+//! [`write_senml()`]: struct.Measurement.html#method.write_senml
+//! [`to_line_protocol()`]: struct.Measurement.html#method.to_line_protocol
+//! [`mqtt`]: mqtt/index.html
 //!
 //! ## The device
 //! The VEML6075 senses UVA and UVB light and incorporates photodiode,
@@ -42,6 +184,35 @@
 //! Application note:
 //! - [Designing the VEML6075 into an Application](https://cdn.sparkfun.com/assets/3/9/d/4/1/designingveml6075.pdf)
 //!
+//! ## Optional features
+//!
+//! - `cli`: Build the `veml6075-cli` Linux command-line tool to validate
+//!   the hardware and to log measurements continuously to a CSV or JSON
+//!   file, to export them as Prometheus gauges over HTTP or to mirror them
+//!   into files in the Linux industrial I/O (IIO) sysfs layout:
+//!   `cargo install veml6075 --features cli`. Implies `linux` and `json`.
+//! - `cover-pmma`, `cover-polycarbonate`, `cover-soda-lime-glass`, `cover-ptfe`:
+//!   Select the cover window preset used by `Calibration::default()`. If
+//!   several are enabled, the first one in this list takes precedence.
+//! - `defmt`: Implement `defmt::Format` for the public types.
+//! - `serde`: Implement `Serialize` and `Deserialize` for `Calibration`,
+//!   `Measurement` and the configuration enums.
+//! - `json`: Serialize measurements as JSON into a caller-provided buffer.
+//!   Implies `serde`.
+//! - `std`: Implement `std::error::Error` for `Error` and provide the
+//!   `SystemClock` wall clock for timestamped measurements and the `logger`
+//!   data logger.
+//! - `linux`: Open the sensor directly from a Linux I²C device path with
+//!   `Veml6075::open()`. Implies `std`.
+//! - `log`: Emit debug and trace messages for register accesses and
+//!   measurements through the [`log`](https://docs.rs/log) crate.
+//! - `veml6070`: Driver for the older VEML6070 UVA light sensor implementing
+//!   the same `UvSensor` trait.
+//! - `sim`: Register-level simulator of the device for host testing, including
+//!   a deterministic synthetic sun. Also builds for `wasm32-unknown-unknown`.
+//! - `test-util`: Helpers to write expectation-based tests against this
+//!   driver using `embedded-hal-mock`. Only for targets with `std`.
+//!
 //! ## Usage examples (see also examples folder)
 //!
 //! Please find additional examples using hardware in this repository: [driver-examples]
@@ -114,21 +285,86 @@
 //! let uvb = sensor.read_uvb_raw().unwrap();
 //! println!("Measurements UVA: {}, UVB: {}", uva, uvb);
 //! ```
+//!
+//! ### Use several sensors behind a PCA9548 I²C multiplexer
+//!
+//! The VEML6075 has a fixed address so several sensors must be connected
+//! to different channels of an I²C multiplexer.
+//!
+//! ```no_run
+//! use linux_embedded_hal::I2cdev;
+//! use veml6075::{Calibration, MuxedVeml6075, DEFAULT_MUX_ADDRESS};
+//!
+//! let dev = I2cdev::new("/dev/i2c-1").unwrap();
+//! let bus = shared_bus::BusManagerSimple::new(dev);
+//! let cal = Calibration::default();
+//! let mut sensor0 =
+//!     MuxedVeml6075::new_muxed(bus.acquire_i2c(), DEFAULT_MUX_ADDRESS, 0, cal).unwrap();
+//! let mut sensor1 =
+//!     MuxedVeml6075::new_muxed(bus.acquire_i2c(), DEFAULT_MUX_ADDRESS, 1, cal).unwrap();
+//! let m0 = sensor0.read().unwrap();
+//! let m1 = sensor1.read().unwrap();
+//! println!("UVI 0: {:2}, UVI 1: {:2}", m0.uv_index, m1.uv_index);
+//! ```
+//!
+//! ### Share the I²C bus with other devices
+//!
+//! The driver only requires the `embedded-hal` I²C traits so it can be used
+//! together with other devices on the same bus through bus-sharing adapters
+//! like the ones provided by the [`shared-bus`](https://docs.rs/shared-bus) crate.
+//!
+//! ```no_run
+//! use linux_embedded_hal::I2cdev;
+//! use veml6075::{Calibration, Veml6075};
+//!
+//! let dev = I2cdev::new("/dev/i2c-1").unwrap();
+//! let bus = shared_bus::BusManagerSimple::new(dev);
+//! let mut sensor = Veml6075::new(bus.acquire_i2c(), Calibration::default());
+//! let mut other_device_bus = bus.acquire_i2c();
+//! // pass `other_device_bus` to another driver...
+//! let m = sensor.read().unwrap();
+//! println!("UVA: {:2}, UVB: {:2}, UVI: {:2}", m.uva, m.uvb, m.uv_index);
+//! ```
 
 #![doc(html_root_url = "https://docs.rs/veml6075/0.2.1")]
 #![deny(unsafe_code)]
 #![deny(missing_docs)]
 #![no_std]
 
+#[cfg(any(feature = "std", feature = "test-util"))]
+extern crate std;
+
 /// All possible errors in this crate
 #[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
 pub enum Error<E> {
     /// I²C bus error
     I2C(E),
+    /// The device ID read does not match the one of a VEML6075
+    InvalidDevice,
+    /// The calibration coefficients are invalid
+    InvalidCalibration,
+    /// No calibration with the given name is registered
+    UnknownCalibration,
+    /// Repeated reads of a data register returned different values
+    ReadMismatch,
+    /// The configuration register does not match the configuration set
+    ConfigMismatch {
+        /// Configuration set through the driver
+        expected: u8,
+        /// Configuration read from the device
+        actual: u8,
+    },
 }
 
 /// Calibrated Measurement
+///
+/// Use [`Measurement::new()`](#method.new) to create one outside of this crate.
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
 pub struct Measurement {
     /// UVA calibrated reading
     pub uva: f32,
@@ -138,8 +374,62 @@ pub struct Measurement {
     pub uv_index: f32,
 }
 
+/// Raw measurement
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RawMeasurement {
+    /// UVA raw reading
+    pub uva: u16,
+    /// UVB raw reading
+    pub uvb: u16,
+    /// UVcomp1 (visible compensation) raw reading
+    pub uvcomp1: u16,
+    /// UVcomp2 (IR compensation) raw reading
+    pub uvcomp2: u16,
+}
+
+/// Raw, compensated and calibrated values from a single acquisition
+///
+/// Use [`FullMeasurement::new()`](#method.new) to create one outside of this crate.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct FullMeasurement {
+    /// Raw readings
+    pub raw: RawMeasurement,
+    /// UVA reading after visible and IR compensation
+    pub uva_compensated: f32,
+    /// UVB reading after visible and IR compensation
+    pub uvb_compensated: f32,
+    /// Calibrated measurement including the angular and cover
+    /// corrections and the UV index
+    pub measurement: Measurement,
+}
+
+/// UV index exposure category as defined by the WHO
+///
+/// The UV index is rounded to the nearest integer before classification.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum UvIndexClass {
+    /// Low (0-2)
+    Low,
+    /// Moderate (3-5)
+    Moderate,
+    /// High (6-7)
+    High,
+    /// Very high (8-10)
+    VeryHigh,
+    /// Extreme (11+)
+    Extreme,
+}
+
 /// Integration time
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum IntegrationTime {
     /// 50 ms
     Ms50,
@@ -155,6 +445,8 @@ pub enum IntegrationTime {
 
 /// Dynamic setting
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DynamicSetting {
     /// Normal dynamic setting
     Normal,
@@ -164,6 +456,8 @@ pub enum DynamicSetting {
 
 /// Operating mode
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Mode {
     /// Continuous measurement (default)
     Continuous,
@@ -171,8 +465,22 @@ pub enum Mode {
     ActiveForce,
 }
 
+#[cfg(feature = "serde")]
+fn unity() -> f32 {
+    1.0
+}
+
 /// Calibration coefficients
+///
+/// Use [`Calibration::new()`](#method.new) and the `with_*()` methods to
+/// create one outside of this crate.
+///
+/// When deserializing, the angular correction, transmission and gain trim
+/// factors default to 1.0 if they are missing.
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
 pub struct Calibration {
     /// UVA visible (a) coefficient
     pub uva_visible: f32,
@@ -186,29 +494,613 @@ pub struct Calibration {
     pub uva_responsivity: f32,
     /// UVB responsivity
     pub uvb_responsivity: f32,
+    /// Angular response correction factor applied to the UVA and UVB values
+    ///
+    /// This compensates for the reduced response to oblique light when the
+    /// sensor is mounted at an angle or behind a window (default: 1.0).
+    #[cfg_attr(feature = "serde", serde(default = "unity"))]
+    pub angular_correction: f32,
+    /// UVA transmission of the cover window (0.0-1.0)
+    ///
+    /// The UVA value is divided by this to report the value that would be
+    /// measured without the cover (default: 1.0).
+    #[cfg_attr(feature = "serde", serde(default = "unity"))]
+    pub uva_transmission: f32,
+    /// UVB transmission of the cover window (0.0-1.0)
+    ///
+    /// The UVB value is divided by this to report the value that would be
+    /// measured without the cover (default: 1.0).
+    #[cfg_attr(feature = "serde", serde(default = "unity"))]
+    pub uvb_transmission: f32,
+    /// UVA gain trim factor applied after the compensation (default: 1.0)
+    ///
+    /// This expresses per-unit trimming without changing the other coefficients.
+    #[cfg_attr(feature = "serde", serde(default = "unity"))]
+    pub uva_gain: f32,
+    /// UVB gain trim factor applied after the compensation (default: 1.0)
+    #[cfg_attr(feature = "serde", serde(default = "unity"))]
+    pub uvb_gain: f32,
 }
 
+/// I²C transaction retry policy
+///
+/// Failed I²C transactions are retried up to `retries` additional times
+/// before an error is returned. The default performs no retries.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RetryPolicy {
+    /// Number of additional attempts after a failed transaction
+    pub retries: u8,
+    /// Function called before each retry with the retry number (starting at 1).
+    ///
+    /// This can be used to wait some time before retrying.
+    pub on_retry: Option<fn(u8)>,
+}
+
+/// I²C bus error statistics
+///
+/// See [`stats()`](struct.Veml6075.html#method.stats).
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BusStats {
+    /// Number of failed I²C transactions, including those retried
+    pub errors: u32,
+    /// Number of retried I²C transactions
+    pub retries: u32,
+    /// Number of successful recoveries through `recover()`
+    pub recoveries: u32,
+}
+
+/// Register access direction reported to the bus observer
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Direction {
+    /// Register read
+    Read,
+    /// Register write
+    Write,
+}
+
+/// Cover window material in front of the sensor
+///
+/// See [`Calibration::for_cover()`](struct.Calibration.html#method.for_cover).
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CoverType {
+    /// No cover (open air)
+    None,
+    /// PMMA (acrylic)
+    Pmma,
+    /// Polycarbonate
+    Polycarbonate,
+    /// Soda-lime glass
+    SodaLimeGlass,
+    /// PTFE diffuser
+    Ptfe,
+}
+
+/// Calibrations for the cover types, named after the `cover-*` features.
+///
+/// This is the default calibration registry, see
+/// [`set_calibration_by_name()`](struct.Veml6075.html#method.set_calibration_by_name).
+pub const COVER_CALIBRATIONS: &[(&str, Calibration)] = &[
+    ("none", Calibration::for_cover(CoverType::None)),
+    ("pmma", Calibration::for_cover(CoverType::Pmma)),
+    (
+        "polycarbonate",
+        Calibration::for_cover(CoverType::Polycarbonate),
+    ),
+    (
+        "soda-lime-glass",
+        Calibration::for_cover(CoverType::SodaLimeGlass),
+    ),
+    ("ptfe", Calibration::for_cover(CoverType::Ptfe)),
+];
+
+/// Cover type used by `Calibration::default()`.
+///
+/// This is selected at compile time with the `cover-*` features. If several
+/// of them are enabled, the first one in this order is used: `cover-pmma`,
+/// `cover-polycarbonate`, `cover-soda-lime-glass`, `cover-ptfe`.
+pub const DEFAULT_COVER: CoverType = if cfg!(feature = "cover-pmma") {
+    CoverType::Pmma
+} else if cfg!(feature = "cover-polycarbonate") {
+    CoverType::Polycarbonate
+} else if cfg!(feature = "cover-soda-lime-glass") {
+    CoverType::SodaLimeGlass
+} else if cfg!(feature = "cover-ptfe") {
+    CoverType::Ptfe
+} else {
+    CoverType::None
+};
+
+/// Margin added to the integration time for the minimum sample interval, in percent.
+///
+/// See [`min_sample_interval()`](struct.Veml6075.html#method.min_sample_interval).
+pub const SAMPLE_INTERVAL_MARGIN_PERCENT: u64 = 10;
+
 /// Veml6075 device driver.
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct Veml6075<I2C> {
     /// The concrete I²C device implementation.
     i2c: I2C,
+    /// I²C device address.
+    address: u8,
     /// Configuration register status.
     config: u8,
     calibration: Calibration,
+    prepared: PreparedCalibration,
+    cached: Option<TimestampedMeasurement>,
+    retry_policy: RetryPolicy,
+    stats: BusStats,
+    bus_observer: Option<fn(Direction, u8, u16)>,
+    quality: QualityState,
+    double_read: bool,
+    read_mismatches: u32,
+    /// No complete integration period has elapsed since the last configuration change.
+    settling: bool,
+    calibrations: &'static [(&'static str, Calibration)],
+}
+
+impl<I2C: Default> Default for Veml6075<I2C> {
+    fn default() -> Self {
+        Veml6075 {
+            i2c: I2C::default(),
+            address: DEVICE_ADDRESS,
+            config: 0,
+            calibration: Calibration::default(),
+            prepared: PreparedCalibration::default(),
+            cached: None,
+            retry_policy: RetryPolicy::default(),
+            stats: BusStats::default(),
+            bus_observer: None,
+            quality: QualityState::default(),
+            double_read: false,
+            read_mismatches: 0,
+            settling: false,
+            calibrations: COVER_CALIBRATIONS,
+        }
+    }
+}
+
+#[cfg(feature = "log")]
+macro_rules! debug {
+    ($($arg:tt)*) => { log::debug!($($arg)*) };
+}
+#[cfg(not(feature = "log"))]
+macro_rules! debug {
+    ($($arg:tt)*) => {};
+}
+
+#[cfg(feature = "log")]
+macro_rules! trace {
+    ($($arg:tt)*) => { log::trace!($($arg)*) };
+}
+#[cfg(not(feature = "log"))]
+macro_rules! trace {
+    ($($arg:tt)*) => {};
 }
 
+mod array;
+pub use crate::array::Veml6075Array;
+mod auto_exposure;
+pub use crate::auto_exposure::AutoExposure;
+mod blob;
+pub use crate::blob::{
+    migrate_calibration_blob, BlobError, CALIBRATION_BLOB_LEN, CALIBRATION_BLOB_VERSION,
+};
+mod borrowed;
+pub use crate::borrowed::BorrowedI2c;
+mod builder;
+pub use crate::builder::Builder;
+pub mod bthome;
+mod clock;
+#[cfg(feature = "std")]
+pub use crate::clock::SystemClock;
+pub use crate::clock::{Clock, TimestampedMeasurement};
+mod conversion;
+mod csv;
 mod device_impl;
+mod display;
+pub mod dose;
+pub mod filter;
+mod fmt_util;
+mod guard;
+mod hdr;
+mod headroom;
+pub use crate::headroom::{check_headroom, Headroom};
+pub mod hil;
+mod influx;
+pub use crate::guard::{EnabledScope, ShutdownOnDrop};
+mod iter;
+pub use crate::iter::Measurements;
+#[cfg(feature = "linux")]
+mod linux;
+#[cfg(feature = "std")]
+pub mod logger;
+mod low_power;
+pub mod lpp;
+pub use crate::low_power::LowPowerSampler;
+mod mux;
+mod noise;
+pub use crate::mux::{MuxChannel, MuxedVeml6075, DEFAULT_MUX_ADDRESS};
+pub use crate::noise::{ChannelNoise, NoiseEstimate, Snr};
+pub mod mqtt;
+mod ops;
+mod oversampler;
+pub use crate::oversampler::{Oversampler, RawSum};
+mod poller;
+pub use crate::poller::{PollState, Poller};
+pub mod power;
+mod prometheus;
+mod quality;
+use crate::quality::QualityState;
+pub use crate::quality::{FlaggedMeasurement, MeasurementFlags};
+pub mod regs;
+pub use crate::regs::DEVICE_ADDRESS;
+mod sampler;
+mod self_test;
+pub use crate::self_test::{SelfTestReport, COMPENSATION_TOLERANCE};
+mod scheduler;
+mod split;
+mod thresholds;
+pub use crate::sampler::Sampler;
+pub use crate::thresholds::{Event, Thresholds, EVENT_QUEUE_LEN, MAX_THRESHOLD_LEVELS};
+mod senml;
+mod snapshot;
+pub use crate::snapshot::{ConfigSnapshot, SuspendToken};
+mod sink;
+pub use crate::scheduler::Scheduler;
+pub use crate::sink::{MeasurementSink, Pump};
+pub use crate::split::{Configurator, Reader};
+mod uv_sensor;
+pub use crate::uv_sensor::UvSensor;
+#[cfg(feature = "veml6070")]
+mod veml6070;
+#[cfg(feature = "veml6070")]
+pub use crate::veml6070::{Veml6070, Veml6070IntegrationTime};
+#[cfg(feature = "sim")]
+pub mod sim;
+#[cfg(feature = "test-util")]
+pub mod test_util;
+mod transaction;
+pub mod trend;
+pub mod wire;
+pub use crate::transaction::TransactionI2c;
+mod wizard;
+pub use crate::wizard::{CalibrationWizard, WizardState};
+
+#[cfg(feature = "json")]
+mod json;
 
 impl Default for Calibration {
+    /// Calibration for the [`DEFAULT_COVER`](constant.DEFAULT_COVER.html) selected
+    /// at compile time. Without any `cover-*` feature this is the open-air calibration.
     fn default() -> Self {
+        Calibration::for_cover(DEFAULT_COVER)
+    }
+}
+
+impl Calibration {
+    /// Create new calibration coefficients.
+    ///
+    /// The angular correction, the transmission coefficients and the gain
+    /// trim factors are set to 1.0.
+    pub const fn new(
+        uva_visible: f32,
+        uva_ir: f32,
+        uvb_visible: f32,
+        uvb_ir: f32,
+        uva_responsivity: f32,
+        uvb_responsivity: f32,
+    ) -> Self {
         Calibration {
-            uva_visible: 2.22,
-            uva_ir: 1.33,
-            uvb_visible: 2.95,
-            uvb_ir: 1.74,
-            uva_responsivity: 0.001_461,
-            uvb_responsivity: 0.002_591,
+            uva_visible,
+            uva_ir,
+            uvb_visible,
+            uvb_ir,
+            uva_responsivity,
+            uvb_responsivity,
+            angular_correction: 1.0,
+            uva_transmission: 1.0,
+            uvb_transmission: 1.0,
+            uva_gain: 1.0,
+            uvb_gain: 1.0,
+        }
+    }
+
+    /// UVA visible (a) coefficient
+    pub fn uva_visible(&self) -> f32 {
+        self.uva_visible
+    }
+
+    /// UVA IR (b) coefficient
+    pub fn uva_ir(&self) -> f32 {
+        self.uva_ir
+    }
+
+    /// UVB visible (c) coefficient
+    pub fn uvb_visible(&self) -> f32 {
+        self.uvb_visible
+    }
+
+    /// UVB IR (d) coefficient
+    pub fn uvb_ir(&self) -> f32 {
+        self.uvb_ir
+    }
+
+    /// UVA responsivity
+    pub fn uva_responsivity(&self) -> f32 {
+        self.uva_responsivity
+    }
+
+    /// UVB responsivity
+    pub fn uvb_responsivity(&self) -> f32 {
+        self.uvb_responsivity
+    }
+
+    /// Angular response correction factor
+    pub fn angular_correction(&self) -> f32 {
+        self.angular_correction
+    }
+
+    /// UVA transmission of the cover window
+    pub fn uva_transmission(&self) -> f32 {
+        self.uva_transmission
+    }
+
+    /// UVB transmission of the cover window
+    pub fn uvb_transmission(&self) -> f32 {
+        self.uvb_transmission
+    }
+
+    /// UVA gain trim factor
+    pub fn uva_gain(&self) -> f32 {
+        self.uva_gain
+    }
+
+    /// UVB gain trim factor
+    pub fn uvb_gain(&self) -> f32 {
+        self.uvb_gain
+    }
+
+    /// Whether the coefficients yield finite measurements.
+    ///
+    /// All coefficients must be finite and the transmission coefficients
+    /// must be greater than zero.
+    pub fn is_valid(&self) -> bool {
+        [
+            self.uva_visible,
+            self.uva_ir,
+            self.uvb_visible,
+            self.uvb_ir,
+            self.uva_responsivity,
+            self.uvb_responsivity,
+            self.angular_correction,
+            self.uva_gain,
+            self.uvb_gain,
+        ]
+        .iter()
+        .all(|c| c.is_finite())
+            && self.uva_transmission.is_finite()
+            && self.uva_transmission > 0.0
+            && self.uvb_transmission.is_finite()
+            && self.uvb_transmission > 0.0
+    }
+
+    /// Set the angular response correction factor.
+    pub const fn with_angular_correction(mut self, angular_correction: f32) -> Self {
+        self.angular_correction = angular_correction;
+        self
+    }
+
+    /// Set the UVA and UVB gain trim factors.
+    pub const fn with_gain_trim(mut self, uva_gain: f32, uvb_gain: f32) -> Self {
+        self.uva_gain = uva_gain;
+        self.uvb_gain = uvb_gain;
+        self
+    }
+
+    /// Set the cover window transmission coefficients.
+    pub const fn with_transmission(mut self, uva_transmission: f32, uvb_transmission: f32) -> Self {
+        self.uva_transmission = uva_transmission;
+        self.uvb_transmission = uvb_transmission;
+        self
+    }
+
+    /// Default calibration with typical transmission coefficients for a cover material.
+    ///
+    /// The transmission values are approximate figures for cover windows
+    /// around 1-2 mm thick and vary considerably between material grades
+    /// and thicknesses. They are meant as a starting point: products should
+    /// measure the actual transmission of their enclosure.
+    ///
+    /// Polycarbonate blocks almost all UVB so the compensated UVB value
+    /// behind it is very noisy.
+    ///
+    /// | Cover           | UVA transmission | UVB transmission |
+    /// |-----------------|------------------|------------------|
+    /// | None            | 1.00             | 1.00             |
+    /// | PMMA            | 0.85             | 0.50             |
+    /// | Polycarbonate   | 0.10             | 0.01             |
+    /// | Soda-lime glass | 0.80             | 0.10             |
+    /// | PTFE            | 0.40             | 0.35             |
+    pub const fn for_cover(cover: CoverType) -> Self {
+        let (uva_transmission, uvb_transmission) = match cover {
+            CoverType::None => (1.0, 1.0),
+            CoverType::Pmma => (0.85, 0.5),
+            CoverType::Polycarbonate => (0.1, 0.01),
+            CoverType::SodaLimeGlass => (0.8, 0.1),
+            CoverType::Ptfe => (0.4, 0.35),
+        };
+        Calibration::new(2.22, 1.33, 2.95, 1.74, 0.001_461, 0.002_591)
+            .with_transmission(uva_transmission, uvb_transmission)
+    }
+
+    /// Whether the visible and IR compensation coefficients are all zero.
+    pub(crate) fn is_uncompensated(&self) -> bool {
+        self.uva_visible == 0.0
+            && self.uva_ir == 0.0
+            && self.uvb_visible == 0.0
+            && self.uvb_ir == 0.0
+    }
+
+    /// Calculate the UVA and UVB readings after visible and IR compensation.
+    pub(crate) fn compensate_visible_ir(&self, raw: &RawMeasurement) -> (f32, f32) {
+        let (uvcomp1, uvcomp2) = (f32::from(raw.uvcomp1), f32::from(raw.uvcomp2));
+        let uva = f32::from(raw.uva) - self.uva_visible * uvcomp1 - self.uva_ir * uvcomp2;
+        let uvb = f32::from(raw.uvb) - self.uvb_visible * uvcomp1 - self.uvb_ir * uvcomp2;
+        (uva, uvb)
+    }
+
+    /// Calculate the calibrated measurement from raw readings.
+    pub fn compensate(&self, raw: &RawMeasurement) -> Measurement {
+        PreparedCalibration::from(*self).compensate(raw)
+    }
+}
+
+/// Calibration with the coefficients combined into the minimal number of
+/// multipliers. The driver prepares it when the calibration is set so that
+/// each read avoids divisions.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct PreparedCalibration {
+    uva: f32,
+    uva_visible: f32,
+    uva_ir: f32,
+    uvb: f32,
+    uvb_visible: f32,
+    uvb_ir: f32,
+    uva_uvi: f32,
+    uvb_uvi: f32,
+}
+
+impl From<Calibration> for PreparedCalibration {
+    fn from(c: Calibration) -> Self {
+        let uva = c.angular_correction * c.uva_gain / c.uva_transmission;
+        let uvb = c.angular_correction * c.uvb_gain / c.uvb_transmission;
+        PreparedCalibration {
+            uva,
+            uva_visible: c.uva_visible * uva,
+            uva_ir: c.uva_ir * uva,
+            uvb,
+            uvb_visible: c.uvb_visible * uvb,
+            uvb_ir: c.uvb_ir * uvb,
+            uva_uvi: c.uva_responsivity / 2.0,
+            uvb_uvi: c.uvb_responsivity / 2.0,
+        }
+    }
+}
+
+impl Default for PreparedCalibration {
+    fn default() -> Self {
+        Calibration::default().into()
+    }
+}
+
+impl PreparedCalibration {
+    fn compensate(&self, raw: &RawMeasurement) -> Measurement {
+        self.compensate_counts(
+            f32::from(raw.uva),
+            f32::from(raw.uvb),
+            f32::from(raw.uvcomp1),
+            f32::from(raw.uvcomp2),
+        )
+    }
+
+    pub(crate) fn compensate_counts(
+        &self,
+        uva: f32,
+        uvb: f32,
+        uvcomp1: f32,
+        uvcomp2: f32,
+    ) -> Measurement {
+        let uva = uva * self.uva - self.uva_visible * uvcomp1 - self.uva_ir * uvcomp2;
+        let uvb = uvb * self.uvb - self.uvb_visible * uvcomp1 - self.uvb_ir * uvcomp2;
+        let uv_index = uva * self.uva_uvi + uvb * self.uvb_uvi;
+        Measurement { uva, uvb, uv_index }
+    }
+}
+
+impl Measurement {
+    /// Create a calibrated measurement.
+    pub const fn new(uva: f32, uvb: f32, uv_index: f32) -> Self {
+        Measurement { uva, uvb, uv_index }
+    }
+
+    /// UVA calibrated reading
+    pub fn uva(&self) -> f32 {
+        self.uva
+    }
+
+    /// UVB calibrated reading
+    pub fn uvb(&self) -> f32 {
+        self.uvb
+    }
+
+    /// UV index
+    pub fn uv_index(&self) -> f32 {
+        self.uv_index
+    }
+
+    /// UV index exposure category of this measurement.
+    pub fn uv_index_class(&self) -> UvIndexClass {
+        UvIndexClass::from_uv_index(self.uv_index)
+    }
+}
+
+impl FullMeasurement {
+    /// Create a full measurement.
+    pub const fn new(
+        raw: RawMeasurement,
+        uva_compensated: f32,
+        uvb_compensated: f32,
+        measurement: Measurement,
+    ) -> Self {
+        FullMeasurement {
+            raw,
+            uva_compensated,
+            uvb_compensated,
+            measurement,
+        }
+    }
+
+    /// Raw readings
+    pub fn raw(&self) -> RawMeasurement {
+        self.raw
+    }
+
+    /// UVA reading after visible and IR compensation
+    pub fn uva_compensated(&self) -> f32 {
+        self.uva_compensated
+    }
+
+    /// UVB reading after visible and IR compensation
+    pub fn uvb_compensated(&self) -> f32 {
+        self.uvb_compensated
+    }
+
+    /// Calibrated measurement
+    pub fn measurement(&self) -> Measurement {
+        self.measurement
+    }
+}
+
+impl UvIndexClass {
+    /// Classify a UV index value.
+    ///
+    /// NaN is classified as `Low`, like negative values.
+    pub fn from_uv_index(uv_index: f32) -> Self {
+        if uv_index.is_nan() || uv_index < 2.5 {
+            UvIndexClass::Low
+        } else if uv_index < 5.5 {
+            UvIndexClass::Moderate
+        } else if uv_index < 7.5 {
+            UvIndexClass::High
+        } else if uv_index < 10.5 {
+            UvIndexClass::VeryHigh
+        } else {
+            UvIndexClass::Extreme
         }
     }
 }
@@ -0,0 +1,31 @@
+//! Linux convenience constructors
+use crate::regs::DEVICE_ID;
+use crate::{Calibration, Error, Veml6075};
+use linux_embedded_hal::{i2cdev::linux::LinuxI2CError, I2cdev};
+use std::path::Path;
+
+impl Veml6075<I2cdev> {
+    /// Open the I²C device at the given path with the default calibration.
+    ///
+    /// ```no_run
+    /// use veml6075::Veml6075;
+    ///
+    /// let mut sensor = Veml6075::open("/dev/i2c-1").unwrap();
+    /// sensor.enable().unwrap();
+    /// ```
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error<LinuxI2CError>> {
+        let dev = I2cdev::new(path).map_err(Error::I2C)?;
+        Ok(Veml6075::new(dev, Calibration::default()))
+    }
+
+    /// Open the I²C device at the given path and verify the device ID.
+    ///
+    /// Returns `Error::InvalidDevice` if the device ID does not match.
+    pub fn open_verified<P: AsRef<Path>>(path: P) -> Result<Self, Error<LinuxI2CError>> {
+        let mut sensor = Self::open(path)?;
+        if sensor.read_device_id()? != DEVICE_ID {
+            return Err(Error::InvalidDevice);
+        }
+        Ok(sensor)
+    }
+}
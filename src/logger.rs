@@ -0,0 +1,258 @@
+//! Data logger with pluggable storage
+//!
+//! The [`Logger`] samples a sensor on an interval and appends the
+//! timestamped measurements to a [`Storage`] backend such as a [`Csv`] or
+//! [`JsonLines`] file.
+//!
+//! ```no_run
+//! use std::time::Duration;
+//! use veml6075::{logger::{Csv, Logger}, SystemClock, UvSensor};
+//!
+//! fn log<S: UvSensor>(sensor: S) -> ! {
+//!     let storage = Csv::append("uv.csv").unwrap();
+//!     let mut logger = Logger::new(sensor, storage, SystemClock, Duration::from_secs(60));
+//!     logger.set_buffer_len(10);
+//!     logger.run()
+//! }
+//! ```
+use crate::{Clock, TimestampedMeasurement, UvSensor};
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, Write},
+    path::Path,
+    string::String,
+    thread,
+    time::Duration,
+    vec::Vec,
+};
+
+/// Maximum number of records kept while the storage is failing.
+///
+/// The oldest records are discarded beyond this.
+pub const MAX_PENDING: usize = 1024;
+
+/// Maximum factor by which the interval is extended after consecutive errors.
+pub const MAX_BACKOFF: u32 = 64;
+
+/// Storage backend for logged measurements.
+pub trait Storage {
+    /// Append the records to the storage.
+    ///
+    /// On error, the records are retried on the next write.
+    fn write(&mut self, records: &[TimestampedMeasurement]) -> io::Result<()>;
+}
+
+/// CSV storage, see [`Measurement::write_csv()`](../struct.Measurement.html#method.write_csv).
+#[derive(Debug)]
+pub struct Csv<W> {
+    writer: W,
+    header_pending: bool,
+}
+
+impl<W: Write> Csv<W> {
+    /// Create a CSV storage writing the header before the first record.
+    pub fn new(writer: W) -> Self {
+        Csv {
+            writer,
+            header_pending: true,
+        }
+    }
+
+    /// Destroy the storage, return the writer.
+    pub fn destroy(self) -> W {
+        self.writer
+    }
+}
+
+impl Csv<File> {
+    /// Open a CSV file for appending, creating it if necessary.
+    ///
+    /// The header is only written to empty files.
+    pub fn append<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let header_pending = file.metadata()?.len() == 0;
+        Ok(Csv {
+            writer: file,
+            header_pending,
+        })
+    }
+}
+
+impl<W: Write> Storage for Csv<W> {
+    fn write(&mut self, records: &[TimestampedMeasurement]) -> io::Result<()> {
+        let mut s = String::new();
+        if self.header_pending {
+            crate::Measurement::write_csv_header(&mut s).map_err(to_io)?;
+        }
+        for r in records {
+            r.measurement.write_csv(&mut s, r.at).map_err(to_io)?;
+        }
+        self.writer.write_all(s.as_bytes())?;
+        self.writer.flush()?;
+        self.header_pending = false;
+        Ok(())
+    }
+}
+
+/// JSON lines storage.
+///
+/// Each record is written as a line like
+/// `{"at":1000,"uva":1.5,"uvb":2.25,"uvi":3}`.
+#[derive(Debug)]
+pub struct JsonLines<W> {
+    writer: W,
+}
+
+impl<W: Write> JsonLines<W> {
+    /// Create a JSON lines storage.
+    pub fn new(writer: W) -> Self {
+        JsonLines { writer }
+    }
+
+    /// Destroy the storage, return the writer.
+    pub fn destroy(self) -> W {
+        self.writer
+    }
+}
+
+impl JsonLines<File> {
+    /// Open a JSON lines file for appending, creating it if necessary.
+    pub fn append<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(JsonLines { writer: file })
+    }
+}
+
+impl<W: Write> Storage for JsonLines<W> {
+    fn write(&mut self, records: &[TimestampedMeasurement]) -> io::Result<()> {
+        for r in records {
+            let m = r.measurement;
+            writeln!(
+                self.writer,
+                "{{\"at\":{},\"uva\":{},\"uvb\":{},\"uvi\":{}}}",
+                r.at, m.uva, m.uvb, m.uv_index
+            )?;
+        }
+        self.writer.flush()
+    }
+}
+
+fn to_io(_: core::fmt::Error) -> io::Error {
+    io::Error::from(io::ErrorKind::Other)
+}
+
+/// Logger error
+#[derive(Debug)]
+pub enum LogError<E> {
+    /// Sensor error
+    Sensor(E),
+    /// Storage error. The records are kept and retried.
+    Storage(io::Error),
+}
+
+/// Data logger sampling a sensor on an interval.
+///
+/// The measurements are buffered and written to the storage in batches of
+/// [`set_buffer_len()`](#method.set_buffer_len) records. After an error the
+/// interval is doubled on each consecutive error up to [`MAX_BACKOFF`]
+/// times the configured interval.
+#[derive(Debug)]
+pub struct Logger<S, W, C> {
+    sensor: S,
+    storage: W,
+    clock: C,
+    interval: Duration,
+    buffer_len: usize,
+    pending: Vec<TimestampedMeasurement>,
+    backoff: u32,
+}
+
+impl<S, W, C> Logger<S, W, C>
+where
+    S: UvSensor,
+    W: Storage,
+    C: Clock,
+{
+    /// Create a new logger. The sensor must be enabled beforehand.
+    ///
+    /// Each measurement is written to the storage immediately.
+    pub fn new(sensor: S, storage: W, clock: C, interval: Duration) -> Self {
+        Logger {
+            sensor,
+            storage,
+            clock,
+            interval,
+            buffer_len: 1,
+            pending: Vec::new(),
+            backoff: 1,
+        }
+    }
+
+    /// Set the number of records written to the storage at once.
+    pub fn set_buffer_len(&mut self, len: usize) {
+        self.buffer_len = len.max(1);
+    }
+
+    /// Destroy the logger, return the sensor and the storage.
+    ///
+    /// Buffered records which were not written yet are discarded.
+    pub fn destroy(self) -> (S, W) {
+        (self.sensor, self.storage)
+    }
+
+    /// Number of buffered records not yet written to the storage.
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Time to wait before the next sample, including the error backoff.
+    pub fn next_delay(&self) -> Duration {
+        self.interval * self.backoff
+    }
+
+    /// Read a measurement and write the buffer to the storage if it is full.
+    pub fn sample(&mut self) -> Result<(), LogError<S::Error>> {
+        let measurement = match self.sensor.read_uv() {
+            Ok(m) => m,
+            Err(e) => return Err(self.failed(LogError::Sensor(e))),
+        };
+        if self.pending.len() == MAX_PENDING {
+            self.pending.remove(0);
+        }
+        self.pending.push(TimestampedMeasurement {
+            at: self.clock.now_ms(),
+            measurement,
+        });
+        if self.pending.len() >= self.buffer_len {
+            self.flush()
+        } else {
+            self.backoff = 1;
+            Ok(())
+        }
+    }
+
+    /// Write all buffered records to the storage.
+    pub fn flush(&mut self) -> Result<(), LogError<S::Error>> {
+        if let Err(e) = self.storage.write(&self.pending) {
+            return Err(self.failed(LogError::Storage(e)));
+        }
+        self.pending.clear();
+        self.backoff = 1;
+        Ok(())
+    }
+
+    /// Sample forever, waiting [`next_delay()`](#method.next_delay) between samples.
+    ///
+    /// Errors are retried with backoff.
+    pub fn run(&mut self) -> ! {
+        loop {
+            let _ = self.sample();
+            thread::sleep(self.next_delay());
+        }
+    }
+
+    fn failed(&mut self, e: LogError<S::Error>) -> LogError<S::Error> {
+        self.backoff = (self.backoff * 2).min(MAX_BACKOFF);
+        e
+    }
+}
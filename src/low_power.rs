@@ -0,0 +1,54 @@
+//! Low-power duty-cycled sampling
+use crate::{Error, Measurement, Mode, Veml6075};
+use embedded_hal::blocking::{
+    delay::DelayMs,
+    i2c::{Write, WriteRead},
+};
+
+/// Sampler keeping the sensor shut down between measurements.
+///
+/// Each call to [`next_sample()`](#method.next_sample) enables the sensor,
+/// triggers a one-shot measurement, waits for the integration time,
+/// reads the result and shuts the sensor down again. This minimizes the
+/// average current consumption on battery-powered devices.
+#[derive(Debug)]
+pub struct LowPowerSampler<I2C, D> {
+    sensor: Veml6075<I2C>,
+    delay: D,
+}
+
+impl<I2C, E, D> LowPowerSampler<I2C, D>
+where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+    D: DelayMs<u16>,
+{
+    /// Create a new low-power sampler.
+    ///
+    /// This switches the sensor to active force (one-shot) mode and shuts it down.
+    pub fn new(mut sensor: Veml6075<I2C>, delay: D) -> Result<Self, Error<E>> {
+        sensor.disable()?;
+        sensor.set_mode(Mode::ActiveForce)?;
+        Ok(LowPowerSampler { sensor, delay })
+    }
+
+    /// Destroy the sampler, return the driver and the delay instances.
+    pub fn destroy(self) -> (Veml6075<I2C>, D) {
+        (self.sensor, self.delay)
+    }
+
+    /// Take a measurement and shut the sensor down again.
+    ///
+    /// The sensor is shut down even if the measurement fails.
+    pub fn next_sample(&mut self) -> Result<Measurement, Error<E>> {
+        let result = self.measure();
+        let disabled = self.sensor.disable();
+        let measurement = result?;
+        disabled?;
+        Ok(measurement)
+    }
+
+    fn measure(&mut self) -> Result<Measurement, Error<E>> {
+        self.sensor.enable()?;
+        self.sensor.wait_and_read(&mut self.delay)
+    }
+}
@@ -0,0 +1,52 @@
+//! Cayenne Low Power Payload (LPP) encoding.
+//!
+//! A measurement is encoded as three LPP data items on consecutive channels
+//! so that it can be transmitted from LoRaWAN nodes and decoded by The
+//! Things Network and myDevices Cayenne without custom code:
+//!
+//! | Channel       | Type                | Value                    |
+//! |---------------|---------------------|--------------------------|
+//! | `channel`     | Illuminance (`101`) | UVA, rounded             |
+//! | `channel + 1` | Illuminance (`101`) | UVB, rounded             |
+//! | `channel + 2` | Analog input (`2`)  | UV index, 0.01 precision |
+//!
+//! Values outside of the ranges of the LPP types are saturated.
+//!
+//! ```
+//! use veml6075::{lpp, Measurement};
+//!
+//! let m = Measurement::new(1000.0, 500.0, 1.23);
+//! let payload = lpp::encode(&m, 1);
+//! assert_eq!([1, 101, 0x03, 0xE8, 2, 101, 0x01, 0xF4, 3, 2, 0, 123], payload);
+//! ```
+
+use crate::Measurement;
+
+/// Encoded size of a [`Measurement`](../struct.Measurement.html) in bytes.
+pub const MEASUREMENT_LEN: usize = 12;
+
+const ILLUMINANCE: u8 = 101;
+const ANALOG_INPUT: u8 = 2;
+
+/// Encode a calibrated measurement starting at the given channel.
+pub fn encode(m: &Measurement, channel: u8) -> [u8; MEASUREMENT_LEN] {
+    let uva = (m.uva + 0.5) as u16;
+    let uvb = (m.uvb + 0.5) as u16;
+    let uv_index = round(m.uv_index * 100.0) as i16;
+    let mut data = [0; MEASUREMENT_LEN];
+    data[0..2].copy_from_slice(&[channel, ILLUMINANCE]);
+    data[2..4].copy_from_slice(&uva.to_be_bytes());
+    data[4..6].copy_from_slice(&[channel.wrapping_add(1), ILLUMINANCE]);
+    data[6..8].copy_from_slice(&uvb.to_be_bytes());
+    data[8..10].copy_from_slice(&[channel.wrapping_add(2), ANALOG_INPUT]);
+    data[10..12].copy_from_slice(&uv_index.to_be_bytes());
+    data
+}
+
+fn round(value: f32) -> f32 {
+    if value < 0.0 {
+        value - 0.5
+    } else {
+        value + 0.5
+    }
+}
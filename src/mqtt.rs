@@ -0,0 +1,195 @@
+//! MQTT payloads compatible with Home Assistant.
+//!
+//! The measurements are published as a single JSON state payload and each
+//! value is announced to Home Assistant with an
+//! [MQTT discovery](https://www.home-assistant.io/integrations/mqtt/#mqtt-discovery)
+//! configuration. Only an MQTT client needs to be provided.
+//!
+//! All discovery messages can be generated at once with
+//! [`Device::discovery()`](struct.Device.html#method.discovery), which also
+//! reports the device ID as hardware version:
+//!
+//! ```no_run
+//! # use linux_embedded_hal::I2cdev;
+//! # use veml6075::{mqtt::Device, Calibration, Veml6075};
+//! # let mut sensor = Veml6075::new(I2cdev::new("/dev/i2c-1").unwrap(), Calibration::default());
+//! # let device = Device { node_id: "garden", name: "Garden", state_topic: "veml6075/garden" };
+//! let device_id = sensor.read_device_id().unwrap();
+//! let (mut topic, mut payload) = ([0; 64], [0; 512]);
+//! device
+//!     .discovery(device_id, &mut topic, &mut payload, |topic, payload| {
+//!         // publish `payload` to `topic` with the retain flag set
+//!     })
+//!     .unwrap();
+//! ```
+//!
+//! Individual messages can also be written into any `core::fmt::Write`:
+//!
+//! ```
+//! use veml6075::{mqtt::{Device, Entity}, Measurement};
+//!
+//! let device = Device {
+//!     node_id: "garden",
+//!     name: "Garden UV sensor",
+//!     state_topic: "veml6075/garden/state",
+//! };
+//! let mut topic = String::new();
+//! let mut config = String::new();
+//! for entity in Entity::ALL.iter() {
+//!     topic.clear();
+//!     config.clear();
+//!     device.write_discovery_topic(*entity, &mut topic).unwrap();
+//!     device.write_discovery_config(*entity, &mut config).unwrap();
+//!     // publish `config` to `topic` with the retain flag set
+//! }
+//! let m = Measurement::new(1.5, 2.25, 3.0);
+//! let mut state = String::new();
+//! veml6075::mqtt::write_state(&m, &mut state).unwrap();
+//! assert_eq!("{\"uva\":1.5,\"uvb\":2.25,\"uvi\":3}", state);
+//! // publish `state` to `device.state_topic`
+//! ```
+
+use crate::{fmt_util::SliceWriter, Measurement};
+use core::fmt::{Result, Write};
+
+/// Value announced as a Home Assistant sensor entity
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Entity {
+    /// Calibrated UVA
+    Uva,
+    /// Calibrated UVB
+    Uvb,
+    /// UV index
+    UvIndex,
+}
+
+impl Entity {
+    /// All entities
+    pub const ALL: [Entity; 3] = [Entity::Uva, Entity::Uvb, Entity::UvIndex];
+
+    fn key(self) -> &'static str {
+        match self {
+            Entity::Uva => "uva",
+            Entity::Uvb => "uvb",
+            Entity::UvIndex => "uvi",
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Entity::Uva => "UVA",
+            Entity::Uvb => "UVB",
+            Entity::UvIndex => "UV index",
+        }
+    }
+}
+
+/// Sensor device announced to Home Assistant
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Device<'a> {
+    /// Unique identifier of the device. Only `[a-zA-Z0-9_-]` are allowed.
+    pub node_id: &'a str,
+    /// Name displayed in Home Assistant
+    pub name: &'a str,
+    /// Topic where the state payload is published
+    pub state_topic: &'a str,
+}
+
+impl Device<'_> {
+    /// Write the discovery topic for an entity.
+    ///
+    /// For example: `homeassistant/sensor/garden/uvi/config`.
+    pub fn write_discovery_topic<W: Write>(&self, entity: Entity, writer: &mut W) -> Result {
+        write!(
+            writer,
+            "homeassistant/sensor/{}/{}/config",
+            self.node_id,
+            entity.key()
+        )
+    }
+
+    /// Write the discovery configuration payload for an entity.
+    pub fn write_discovery_config<W: Write>(&self, entity: Entity, writer: &mut W) -> Result {
+        self.write_config(entity, None, writer)
+    }
+
+    /// Generate the discovery messages for all entities.
+    ///
+    /// For each entity, the topic and the configuration payload including
+    /// the device ID as hardware version are written into the given buffers
+    /// and passed to `publish`. Returns an error if a buffer is too small.
+    pub fn discovery<F>(
+        &self,
+        device_id: u16,
+        topic_buffer: &mut [u8],
+        payload_buffer: &mut [u8],
+        mut publish: F,
+    ) -> Result
+    where
+        F: FnMut(&str, &str),
+    {
+        for entity in Entity::ALL.iter() {
+            let mut topic = SliceWriter::new(topic_buffer);
+            self.write_discovery_topic(*entity, &mut topic)?;
+            let mut payload = SliceWriter::new(payload_buffer);
+            self.write_config(*entity, Some(device_id), &mut payload)?;
+            publish(topic.as_str(), payload.as_str());
+        }
+        Ok(())
+    }
+
+    fn write_config<W: Write>(
+        &self,
+        entity: Entity,
+        device_id: Option<u16>,
+        writer: &mut W,
+    ) -> Result {
+        writer.write_str("{\"name\":\"")?;
+        writer.write_str(entity.name())?;
+        write!(
+            writer,
+            "\",\"unique_id\":\"{}_{}\",\"state_topic\":\"",
+            self.node_id,
+            entity.key()
+        )?;
+        write_escaped(writer, self.state_topic)?;
+        write!(
+            writer,
+            "\",\"value_template\":\"{{{{ value_json.{} }}}}\",\
+             \"state_class\":\"measurement\",\"icon\":\"mdi:weather-sunny-alert\",\
+             \"device\":{{\"identifiers\":[\"{}\"],\"name\":\"",
+            entity.key(),
+            self.node_id
+        )?;
+        write_escaped(writer, self.name)?;
+        writer.write_str("\",\"manufacturer\":\"Vishay\",\"model\":\"VEML6075\"")?;
+        if let Some(device_id) = device_id {
+            write!(writer, ",\"hw_version\":\"{:#06x}\"", device_id)?;
+        }
+        writer.write_str("}}")
+    }
+}
+
+/// Write the state payload for a measurement.
+///
+/// For example: `{"uva":1.5,"uvb":2.25,"uvi":3}`.
+pub fn write_state<W: Write>(measurement: &Measurement, writer: &mut W) -> Result {
+    write!(
+        writer,
+        "{{\"uva\":{},\"uvb\":{},\"uvi\":{}}}",
+        measurement.uva, measurement.uvb, measurement.uv_index
+    )
+}
+
+fn write_escaped<W: Write>(writer: &mut W, value: &str) -> Result {
+    for c in value.chars() {
+        match c {
+            '"' => writer.write_str("\\\"")?,
+            '\\' => writer.write_str("\\\\")?,
+            c if (c as u32) < 0x20 => write!(writer, "\\u{:04x}", c as u32)?,
+            c => writer.write_char(c)?,
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,91 @@
+//! Support for sensors behind a PCA954x I²C multiplexer
+use crate::{Calibration, Veml6075};
+use embedded_hal::blocking::i2c::{Write, WriteRead};
+
+/// Default PCA954x I²C multiplexer address (all address pins low).
+pub const DEFAULT_MUX_ADDRESS: u8 = 0x70;
+
+/// I²C bus wrapper selecting a PCA954x multiplexer channel before each transaction.
+///
+/// To use several sensors on different channels, give each one its own
+/// shared bus handle. See the `shared-bus` example in the crate documentation.
+#[derive(Debug)]
+pub struct MuxChannel<I2C> {
+    i2c: I2C,
+    mux_address: u8,
+    channel: u8,
+}
+
+/// Veml6075 device driver for a sensor connected to a PCA954x multiplexer channel.
+pub type MuxedVeml6075<I2C> = Veml6075<MuxChannel<I2C>>;
+
+impl<I2C> MuxChannel<I2C> {
+    /// Create a new multiplexer channel bus wrapper.
+    ///
+    /// Returns `None` if the channel number is not in the range 0-7.
+    pub fn new(i2c: I2C, mux_address: u8, channel: u8) -> Option<Self> {
+        if channel >= 8 {
+            return None;
+        }
+        Some(MuxChannel {
+            i2c,
+            mux_address,
+            channel,
+        })
+    }
+
+    /// Destroy the wrapper, return I²C bus instance.
+    pub fn destroy(self) -> I2C {
+        self.i2c
+    }
+}
+
+impl<I2C, E> MuxChannel<I2C>
+where
+    I2C: Write<Error = E>,
+{
+    fn select(&mut self) -> Result<(), E> {
+        self.i2c.write(self.mux_address, &[1 << self.channel])
+    }
+}
+
+impl<I2C, E> Write for MuxChannel<I2C>
+where
+    I2C: Write<Error = E>,
+{
+    type Error = E;
+
+    fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), E> {
+        self.select()?;
+        self.i2c.write(address, bytes)
+    }
+}
+
+impl<I2C, E> WriteRead for MuxChannel<I2C>
+where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+{
+    type Error = E;
+
+    fn write_read(&mut self, address: u8, bytes: &[u8], buffer: &mut [u8]) -> Result<(), E> {
+        self.select()?;
+        self.i2c.write_read(address, bytes, buffer)
+    }
+}
+
+impl<I2C, E> MuxedVeml6075<I2C>
+where
+    I2C: Write<Error = E>,
+{
+    /// Create new instance of a Veml6075 device connected to a multiplexer channel.
+    ///
+    /// Returns `None` if the channel number is not in the range 0-7.
+    pub fn new_muxed(
+        i2c: I2C,
+        mux_address: u8,
+        channel: u8,
+        calibration: Calibration,
+    ) -> Option<Self> {
+        MuxChannel::new(i2c, mux_address, channel).map(|i2c| Veml6075::new(i2c, calibration))
+    }
+}
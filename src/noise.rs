@@ -0,0 +1,166 @@
+//! Noise estimation
+use crate::{Error, Mode, RawMeasurement, Veml6075};
+use embedded_hal::blocking::{
+    delay::DelayMs,
+    i2c::{Write, WriteRead},
+};
+
+/// Mean and standard deviation of a raw channel, in counts
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChannelNoise {
+    /// Mean
+    pub mean: f32,
+    /// Sample standard deviation
+    pub std_dev: f32,
+}
+
+/// Per-channel noise estimate
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NoiseEstimate {
+    /// UVA channel
+    pub uva: ChannelNoise,
+    /// UVB channel
+    pub uvb: ChannelNoise,
+    /// UVcomp1 channel
+    pub uvcomp1: ChannelNoise,
+    /// UVcomp2 channel
+    pub uvcomp2: ChannelNoise,
+}
+
+/// Signal-to-noise ratio of the UVA and UVB channels
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Snr {
+    /// UVA signal-to-noise ratio
+    pub uva: f32,
+    /// UVB signal-to-noise ratio
+    pub uvb: f32,
+}
+
+impl Snr {
+    /// Lower of the UVA and UVB ratios.
+    pub fn min(&self) -> f32 {
+        self.uva.min(self.uvb)
+    }
+}
+
+impl ChannelNoise {
+    /// Signal-to-noise ratio of the given counts.
+    ///
+    /// The signal is the counts above the mean and the noise the standard
+    /// deviation. Without noise, this is infinite for any signal.
+    pub fn snr(&self, counts: u16) -> f32 {
+        let signal = (f32::from(counts) - self.mean).max(0.0);
+        if signal == 0.0 {
+            0.0
+        } else {
+            signal / self.std_dev
+        }
+    }
+}
+
+impl NoiseEstimate {
+    /// Signal-to-noise ratio of a raw reading relative to this noise estimate.
+    ///
+    /// The estimate should be taken in the dark, for example with a covered
+    /// sensor. This can be used to only accumulate a UV dose when the signal
+    /// is adequate:
+    ///
+    /// ```
+    /// use veml6075::{ChannelNoise, NoiseEstimate, RawMeasurement};
+    ///
+    /// let dark = ChannelNoise { mean: 10.0, std_dev: 2.0 };
+    /// let noise = NoiseEstimate { uva: dark, uvb: dark, ..Default::default() };
+    /// let raw = RawMeasurement { uva: 110, uvb: 30, uvcomp1: 0, uvcomp2: 0 };
+    /// let snr = noise.snr(&raw);
+    /// assert_eq!(50.0, snr.uva);
+    /// assert_eq!(10.0, snr.min());
+    /// ```
+    pub fn snr(&self, raw: &RawMeasurement) -> Snr {
+        Snr {
+            uva: self.uva.snr(raw.uva),
+            uvb: self.uvb.snr(raw.uvb),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Welford {
+    n: u32,
+    mean: f32,
+    m2: f32,
+}
+
+impl Welford {
+    fn add(&mut self, value: u16) {
+        let value = f32::from(value);
+        self.n += 1;
+        let delta = value - self.mean;
+        self.mean += delta / self.n as f32;
+        self.m2 += delta * (value - self.mean);
+    }
+
+    fn result(&self) -> ChannelNoise {
+        let variance = if self.n > 1 {
+            self.m2 / (self.n - 1) as f32
+        } else {
+            0.0
+        };
+        ChannelNoise {
+            mean: self.mean,
+            std_dev: sqrt(variance),
+        }
+    }
+}
+
+// `f32::sqrt()` is not available in `core`.
+fn sqrt(x: f32) -> f32 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    let mut r = x.max(1.0);
+    for _ in 0..32 {
+        r = 0.5 * (r + x / r);
+    }
+    r
+}
+
+impl<I2C, E> Veml6075<I2C>
+where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+{
+    /// Take `n` raw samples and estimate the noise of each channel.
+    ///
+    /// Each sample is triggered (in active force mode) and read after the
+    /// integration time. Cover the sensor to estimate the dark noise. The
+    /// sensor must be enabled beforehand.
+    pub fn estimate_noise<D: DelayMs<u16>>(
+        &mut self,
+        n: u16,
+        delay: &mut D,
+    ) -> Result<NoiseEstimate, Error<E>> {
+        let mut stats: [Welford; 4] = Default::default();
+        for _ in 0..n {
+            if self.mode() == Mode::ActiveForce {
+                self.trigger_measurement()?;
+            }
+            delay.delay_ms(self.integration_time_ms());
+            let raw = self.read_raw()?;
+            let channels = [raw.uva, raw.uvb, raw.uvcomp1, raw.uvcomp2];
+            for (s, value) in stats.iter_mut().zip(channels.iter()) {
+                s.add(*value);
+            }
+        }
+        Ok(NoiseEstimate {
+            uva: stats[0].result(),
+            uvb: stats[1].result(),
+            uvcomp1: stats[2].result(),
+            uvcomp2: stats[3].result(),
+        })
+    }
+}
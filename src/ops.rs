@@ -0,0 +1,49 @@
+//! Arithmetic operations on measurements
+use crate::Measurement;
+use core::ops::{Add, Div, Sub};
+
+impl Add for Measurement {
+    type Output = Measurement;
+
+    fn add(self, other: Measurement) -> Measurement {
+        Measurement {
+            uva: self.uva + other.uva,
+            uvb: self.uvb + other.uvb,
+            uv_index: self.uv_index + other.uv_index,
+        }
+    }
+}
+
+impl Sub for Measurement {
+    type Output = Measurement;
+
+    fn sub(self, other: Measurement) -> Measurement {
+        Measurement {
+            uva: self.uva - other.uva,
+            uvb: self.uvb - other.uvb,
+            uv_index: self.uv_index - other.uv_index,
+        }
+    }
+}
+
+impl Div<f32> for Measurement {
+    type Output = Measurement;
+
+    fn div(self, divisor: f32) -> Measurement {
+        Measurement {
+            uva: self.uva / divisor,
+            uvb: self.uvb / divisor,
+            uv_index: self.uv_index / divisor,
+        }
+    }
+}
+
+impl Measurement {
+    /// Average of the measurements, or `None` if there are none.
+    pub fn mean<I: IntoIterator<Item = Measurement>>(measurements: I) -> Option<Measurement> {
+        let mut iter = measurements.into_iter();
+        let first = iter.next()?;
+        let (sum, count) = iter.fold((first, 1_u32), |(sum, count), m| (sum + m, count + 1));
+        Some(sum / count as f32)
+    }
+}
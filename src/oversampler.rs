@@ -0,0 +1,101 @@
+//! Integer oversampling
+use crate::{Calibration, Measurement, PreparedCalibration, RawMeasurement};
+
+/// Sum of several raw measurements
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct RawSum {
+    /// Sum of the UVA raw readings
+    pub uva: u32,
+    /// Sum of the UVB raw readings
+    pub uvb: u32,
+    /// Sum of the UVcomp1 raw readings
+    pub uvcomp1: u32,
+    /// Sum of the UVcomp2 raw readings
+    pub uvcomp2: u32,
+    /// Number of measurements summed
+    pub count: u16,
+}
+
+impl RawSum {
+    /// Average raw counts, rounded to the nearest integer.
+    ///
+    /// All counts are 0 if the sum is empty.
+    pub fn average(&self) -> RawMeasurement {
+        let count = u32::from(self.count.max(1));
+        let avg = |sum: u32| ((sum + count / 2) / count) as u16;
+        RawMeasurement {
+            uva: avg(self.uva),
+            uvb: avg(self.uvb),
+            uvcomp1: avg(self.uvcomp1),
+            uvcomp2: avg(self.uvcomp2),
+        }
+    }
+
+    /// Calculate the calibrated measurement from the average counts
+    /// keeping the fractional part gained by oversampling.
+    pub fn compensate(&self, calibration: &Calibration) -> Measurement {
+        let count = f32::from(self.count.max(1));
+        PreparedCalibration::from(*calibration).compensate_counts(
+            self.uva as f32 / count,
+            self.uvb as f32 / count,
+            self.uvcomp1 as f32 / count,
+            self.uvcomp2 as f32 / count,
+        )
+    }
+}
+
+/// Accumulator averaging a fixed number of raw measurements.
+///
+/// The readings are summed in integers so no floating-point operations are
+/// needed until the result is used. Averaging several readings improves the
+/// effective resolution in low light.
+///
+/// ```
+/// use veml6075::{Oversampler, RawMeasurement};
+///
+/// let mut oversampler = Oversampler::new(2);
+/// let raw = |uva| RawMeasurement { uva, uvb: 0, uvcomp1: 0, uvcomp2: 0 };
+/// assert_eq!(None, oversampler.add(&raw(3)));
+/// let sum = oversampler.add(&raw(4)).unwrap();
+/// assert_eq!(7, sum.uva);
+/// assert_eq!(4, sum.average().uva);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Oversampler {
+    sum: RawSum,
+    samples: u16,
+}
+
+impl Oversampler {
+    /// Create a new oversampler averaging `samples` measurements (at least 1).
+    pub fn new(samples: u16) -> Self {
+        Oversampler {
+            sum: RawSum::default(),
+            samples: samples.max(1),
+        }
+    }
+
+    /// Add a raw measurement.
+    ///
+    /// When the configured number of samples is reached, the sum is
+    /// returned and the oversampler starts again.
+    pub fn add(&mut self, raw: &RawMeasurement) -> Option<RawSum> {
+        self.sum.uva += u32::from(raw.uva);
+        self.sum.uvb += u32::from(raw.uvb);
+        self.sum.uvcomp1 += u32::from(raw.uvcomp1);
+        self.sum.uvcomp2 += u32::from(raw.uvcomp2);
+        self.sum.count += 1;
+        if self.sum.count < self.samples {
+            return None;
+        }
+        let sum = self.sum;
+        self.reset();
+        Some(sum)
+    }
+
+    /// Discard the measurements added so far.
+    pub fn reset(&mut self) {
+        self.sum = RawSum::default();
+    }
+}
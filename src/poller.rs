@@ -0,0 +1,91 @@
+//! Non-blocking measurement state machine
+use crate::{Error, Measurement, Mode, Veml6075};
+use embedded_hal::blocking::i2c::{Write, WriteRead};
+
+/// State of the [`Poller`](struct.Poller.html)
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum PollState {
+    /// No measurement in progress
+    Idle,
+    /// Measurement in progress for the given time in milliseconds
+    Triggered(u32),
+    /// Measurement finished but not read yet
+    Ready,
+}
+
+/// Measurement state machine advanced by periodic polling.
+///
+/// [`poll()`](#method.poll) is meant to be called at a fixed period, for
+/// example from a timer interrupt, and never blocks. Each call advances the
+/// state machine: a measurement is triggered when idle (in active force
+/// mode), the integration time is waited for and the measurement is then
+/// read and returned. The next measurement starts on the following call.
+///
+/// If reading the measurement fails, the poller stays in the `Ready` state
+/// so that the read is attempted again on the next call.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Poller {
+    period_ms: u32,
+    state: PollState,
+}
+
+impl Poller {
+    /// Create a new poller for the given polling period in milliseconds.
+    pub fn new(period_ms: u32) -> Self {
+        Poller {
+            period_ms,
+            state: PollState::Idle,
+        }
+    }
+
+    /// Current state.
+    pub fn state(&self) -> PollState {
+        self.state
+    }
+
+    /// Abort the measurement in progress, if any.
+    pub fn reset(&mut self) {
+        self.state = PollState::Idle;
+    }
+
+    /// Advance the state machine by one polling period.
+    ///
+    /// Returns the measurement when a cycle completes.
+    pub fn poll<I2C, E>(
+        &mut self,
+        sensor: &mut Veml6075<I2C>,
+    ) -> Result<Option<Measurement>, Error<E>>
+    where
+        I2C: Write<Error = E> + WriteRead<Error = E>,
+    {
+        match self.state {
+            PollState::Idle => {
+                if sensor.mode() == Mode::ActiveForce {
+                    sensor.trigger_measurement()?;
+                }
+                self.state = PollState::Triggered(0);
+                Ok(None)
+            }
+            PollState::Triggered(elapsed_ms) => {
+                let elapsed_ms = elapsed_ms.saturating_add(self.period_ms);
+                if elapsed_ms < u32::from(sensor.integration_time_ms()) {
+                    self.state = PollState::Triggered(elapsed_ms);
+                    return Ok(None);
+                }
+                self.state = PollState::Ready;
+                self.read(sensor)
+            }
+            PollState::Ready => self.read(sensor),
+        }
+    }
+
+    fn read<I2C, E>(&mut self, sensor: &mut Veml6075<I2C>) -> Result<Option<Measurement>, Error<E>>
+    where
+        I2C: WriteRead<Error = E>,
+    {
+        let m = sensor.read()?;
+        self.state = PollState::Idle;
+        Ok(Some(m))
+    }
+}
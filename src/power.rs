@@ -0,0 +1,37 @@
+//! Power consumption estimation.
+//!
+//! The estimation uses the typical supply currents from the datasheet
+//! (VDD = 3.3 V, T = 25 °C).
+
+use crate::{IntegrationTime, Mode};
+
+/// Typical supply current while measuring, in µA.
+pub const ACTIVE_CURRENT_UA: f32 = 480.0;
+
+/// Typical supply current in shutdown, in µA.
+pub const SHUTDOWN_CURRENT_UA: f32 = 0.8;
+
+/// Current in microamperes
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct MicroAmps(pub f32);
+
+/// Estimate the average supply current for a configuration and sample interval.
+///
+/// In continuous mode the sensor is always measuring so the sample interval
+/// does not matter. In active force (one-shot) mode the sensor is assumed to
+/// be shut down between measurements, as done by
+/// [`LowPowerSampler`](../struct.LowPowerSampler.html).
+pub fn estimate(it: IntegrationTime, mode: Mode, sample_interval_ms: u32) -> MicroAmps {
+    match mode {
+        Mode::Continuous => MicroAmps(ACTIVE_CURRENT_UA),
+        Mode::ActiveForce => {
+            let active_ms = 50_u32 << u8::from(it);
+            let duty = if sample_interval_ms <= active_ms {
+                1.0
+            } else {
+                active_ms as f32 / sample_interval_ms as f32
+            };
+            MicroAmps(ACTIVE_CURRENT_UA * duty + SHUTDOWN_CURRENT_UA * (1.0 - duty))
+        }
+    }
+}
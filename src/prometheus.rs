@@ -0,0 +1,23 @@
+//! Prometheus text exposition format
+use crate::Measurement;
+use core::fmt::{Result, Write};
+
+impl Measurement {
+    /// Write the measurement as Prometheus gauges in the text exposition format.
+    ///
+    /// This writes the `veml6075_uva`, `veml6075_uvb` and `veml6075_uvi`
+    /// gauges including their `HELP` and `TYPE` lines.
+    pub fn write_prometheus<W: Write>(&self, writer: &mut W) -> Result {
+        let gauges = [
+            ("uva", "Compensated UVA counts.", self.uva),
+            ("uvb", "Compensated UVB counts.", self.uvb),
+            ("uvi", "UV index.", self.uv_index),
+        ];
+        for (name, help, value) in gauges.iter() {
+            writeln!(writer, "# HELP veml6075_{} {}", name, help)?;
+            writeln!(writer, "# TYPE veml6075_{} gauge", name)?;
+            writeln!(writer, "veml6075_{} {}", name, value)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,120 @@
+//! Measurement quality flags
+use crate::{Error, Measurement, RawMeasurement, Veml6075};
+use embedded_hal::blocking::i2c::WriteRead;
+
+/// Measurement quality flags
+///
+/// ```
+/// use veml6075::MeasurementFlags;
+///
+/// let flags = MeasurementFlags::SATURATED | MeasurementFlags::STALE;
+/// assert!(flags.contains(MeasurementFlags::SATURATED));
+/// assert!(!flags.contains(MeasurementFlags::BELOW_NOISE_FLOOR));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MeasurementFlags(u8);
+
+impl MeasurementFlags {
+    /// A channel reached full scale.
+    pub const SATURATED: Self = MeasurementFlags(1);
+    /// The raw UVA and UVB counts are below the noise floor.
+    pub const BELOW_NOISE_FLOOR: Self = MeasurementFlags(1 << 1);
+    /// The raw counts are identical to the previous flagged reading,
+    /// so the device has probably not completed a new measurement.
+    pub const STALE: Self = MeasurementFlags(1 << 2);
+    /// First reading after the sensor was enabled.
+    pub const FIRST_AFTER_ENABLE: Self = MeasurementFlags(1 << 3);
+
+    /// Flags as a bit field.
+    pub fn bits(self) -> u8 {
+        self.0
+    }
+
+    /// Whether all the flags in `other` are set.
+    pub fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Whether no flag is set.
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl core::ops::BitOr for MeasurementFlags {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        MeasurementFlags(self.0 | rhs.0)
+    }
+}
+
+impl core::ops::BitOrAssign for MeasurementFlags {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+/// Calibrated measurement with its quality flags
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FlaggedMeasurement {
+    /// Calibrated measurement
+    pub measurement: Measurement,
+    /// Quality flags
+    pub flags: MeasurementFlags,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct QualityState {
+    pub(crate) after_enable: bool,
+    last_raw: Option<RawMeasurement>,
+    noise_floor: u16,
+}
+
+impl<I2C> Veml6075<I2C> {
+    /// Set the noise floor in raw counts used for the
+    /// [`BELOW_NOISE_FLOOR`](struct.MeasurementFlags.html#associatedconstant.BELOW_NOISE_FLOOR)
+    /// flag.
+    ///
+    /// The default is 0, which disables the flag.
+    pub fn set_noise_floor(&mut self, counts: u16) {
+        self.quality.noise_floor = counts;
+    }
+}
+
+impl<I2C, E> Veml6075<I2C>
+where
+    I2C: WriteRead<Error = E>,
+{
+    /// Read the sensor data, calculate calibrated values and evaluate the
+    /// quality flags.
+    ///
+    /// All channels are read.
+    pub fn read_flagged(&mut self) -> Result<FlaggedMeasurement, Error<E>> {
+        let mut flags = MeasurementFlags::default();
+        if self.quality.after_enable {
+            flags |= MeasurementFlags::FIRST_AFTER_ENABLE;
+        }
+        let raw = self.read_raw()?;
+        let channels = [raw.uva, raw.uvb, raw.uvcomp1, raw.uvcomp2];
+        if channels.contains(&0xFFFF) {
+            flags |= MeasurementFlags::SATURATED;
+        }
+        let floor = self.quality.noise_floor;
+        if raw.uva < floor && raw.uvb < floor {
+            flags |= MeasurementFlags::BELOW_NOISE_FLOOR;
+        }
+        if self.quality.last_raw == Some(raw) {
+            flags |= MeasurementFlags::STALE;
+        }
+        self.quality.last_raw = Some(raw);
+        Ok(FlaggedMeasurement {
+            measurement: self.prepared.compensate(&raw),
+            flags,
+        })
+    }
+}
@@ -0,0 +1,46 @@
+//! Register map of the device.
+//!
+//! These constants are useful for debugging tools and custom extensions
+//! accessing the device directly.
+
+/// Device I²C address
+pub const DEVICE_ADDRESS: u8 = 0x10;
+
+/// Device ID register value
+pub const DEVICE_ID: u16 = 0x0026;
+
+/// Register addresses (command codes)
+#[derive(Debug)]
+pub struct Register;
+impl Register {
+    /// Configuration register
+    pub const CONFIG: u8 = 0x00;
+    /// UVA data register
+    pub const UVA: u8 = 0x07;
+    /// UVB data register
+    pub const UVB: u8 = 0x09;
+    /// UVcomp1 data register
+    pub const UVCOMP1: u8 = 0x0A;
+    /// UVcomp2 data register
+    pub const UVCOMP2: u8 = 0x0B;
+    /// Device ID register
+    pub const DEVICE_ID: u8 = 0x0C;
+}
+
+/// Configuration register bit flags
+#[derive(Debug)]
+pub struct BitFlags;
+impl BitFlags {
+    /// Shutdown
+    pub const SHUTDOWN: u8 = 0b0000_0001;
+    /// Active force (one-shot) mode
+    pub const UV_AF: u8 = 0b0000_0010;
+    /// Trigger a measurement in active force mode
+    pub const UV_TRIG: u8 = 0b0000_0100;
+    /// High dynamic setting
+    pub const HD: u8 = 0b0000_1000;
+    /// Integration time field mask
+    pub const UV_IT_MASK: u8 = 0b0111_0000;
+    /// Integration time field shift
+    pub const UV_IT_SHIFT: u8 = 4;
+}
@@ -0,0 +1,86 @@
+//! Sampler with automatic timing
+use crate::sink::OnMeasurement;
+use crate::thresholds::EventQueue;
+use crate::{Error, Event, Measurement, Thresholds, Veml6075};
+use embedded_hal::blocking::{
+    delay::DelayMs,
+    i2c::{Write, WriteRead},
+};
+
+/// Driver wrapper owning a delay source to handle measurement timing.
+///
+/// Depending on the operating mode, [`next_sample()`](#method.next_sample)
+/// triggers a measurement (active force mode), waits for the integration
+/// time and reads the result.
+#[derive(Debug)]
+pub struct Sampler<I2C, D> {
+    sensor: Veml6075<I2C>,
+    delay: D,
+    on_measurement: Option<OnMeasurement>,
+    thresholds: Option<Thresholds>,
+    events: EventQueue,
+}
+
+impl<I2C, D> Sampler<I2C, D> {
+    /// Create a new sampler.
+    pub fn new(sensor: Veml6075<I2C>, delay: D) -> Self {
+        Sampler {
+            sensor,
+            delay,
+            on_measurement: None,
+            thresholds: None,
+            events: EventQueue::new(),
+        }
+    }
+
+    /// Set a function to be called whenever a new measurement is read.
+    pub fn set_on_measurement(&mut self, callback: Option<fn(&Measurement)>) {
+        self.on_measurement = callback.map(OnMeasurement);
+    }
+
+    /// Set the UV index levels to report crossings of as events.
+    ///
+    /// See [`next_event()`](#method.next_event).
+    pub fn set_thresholds(&mut self, thresholds: Option<Thresholds>) {
+        self.thresholds = thresholds;
+    }
+
+    /// Take the oldest pending threshold crossing event.
+    ///
+    /// Only the last [`EVENT_QUEUE_LEN`](constant.EVENT_QUEUE_LEN.html)
+    /// events are kept.
+    pub fn next_event(&mut self) -> Option<Event> {
+        self.events.pop()
+    }
+
+    /// Destroy the sampler, return the driver and the delay instances.
+    pub fn destroy(self) -> (Veml6075<I2C>, D) {
+        (self.sensor, self.delay)
+    }
+
+    /// Access the driver, for example to change its configuration.
+    pub fn sensor_mut(&mut self) -> &mut Veml6075<I2C> {
+        &mut self.sensor
+    }
+}
+
+impl<I2C, E, D> Sampler<I2C, D>
+where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+    D: DelayMs<u16>,
+{
+    /// Wait for and read the next calibrated measurement.
+    ///
+    /// The sensor must be enabled beforehand.
+    pub fn next_sample(&mut self) -> Result<Measurement, Error<E>> {
+        let m = self.sensor.wait_and_read(&mut self.delay)?;
+        if let Some(OnMeasurement(callback)) = self.on_measurement {
+            callback(&m);
+        }
+        if let Some(thresholds) = &mut self.thresholds {
+            let events = &mut self.events;
+            thresholds.update(m.uv_index, |e| events.push(e));
+        }
+        Ok(m)
+    }
+}
@@ -0,0 +1,93 @@
+//! Periodic sampling scheduler
+use crate::sink::OnMeasurement;
+use crate::{Error, Measurement, Mode, Veml6075};
+use embedded_hal::blocking::i2c::{Write, WriteRead};
+
+/// Periodic sampling scheduler.
+///
+/// The scheduler is fed with the time elapsed since the last call to
+/// [`tick()`](#method.tick), for example from a periodic timer, and decides
+/// when to trigger and read a measurement. The sample interval can be much
+/// longer than the integration time, which is useful for low-rate logging
+/// in active force (one-shot) mode.
+///
+/// The first measurement is taken on the first tick.
+#[derive(Debug, Clone, Copy)]
+pub struct Scheduler {
+    interval_ms: u32,
+    since_sample_ms: u32,
+    since_trigger_ms: Option<u32>,
+    on_measurement: Option<OnMeasurement>,
+}
+
+impl Scheduler {
+    /// Create a new scheduler with the given sample interval in milliseconds.
+    pub fn new(interval_ms: u32) -> Self {
+        Scheduler {
+            interval_ms,
+            since_sample_ms: interval_ms,
+            since_trigger_ms: None,
+            on_measurement: None,
+        }
+    }
+
+    /// Set a function to be called whenever a new measurement is read.
+    pub fn set_on_measurement(&mut self, callback: Option<fn(&Measurement)>) {
+        self.on_measurement = callback.map(OnMeasurement);
+    }
+
+    /// Sample interval in milliseconds.
+    pub fn interval_ms(&self) -> u32 {
+        self.interval_ms
+    }
+
+    /// Advance the scheduler and trigger or read a measurement if due.
+    ///
+    /// Returns the measurement once it is available.
+    ///
+    /// In active force mode, a measurement is triggered when the interval
+    /// has elapsed and read once the integration time has elapsed. In
+    /// continuous mode, the latest measurement is read when the interval
+    /// has elapsed.
+    pub fn tick<I2C, E>(
+        &mut self,
+        sensor: &mut Veml6075<I2C>,
+        elapsed_ms: u32,
+    ) -> Result<Option<Measurement>, Error<E>>
+    where
+        I2C: Write<Error = E> + WriteRead<Error = E>,
+    {
+        self.since_sample_ms = self.since_sample_ms.saturating_add(elapsed_ms);
+        if let Some(since_trigger) = self.since_trigger_ms {
+            let since_trigger = since_trigger.saturating_add(elapsed_ms);
+            if since_trigger < u32::from(sensor.integration_time_ms()) {
+                self.since_trigger_ms = Some(since_trigger);
+                return Ok(None);
+            }
+            self.since_trigger_ms = None;
+            return self.read(sensor).map(Some);
+        }
+        if self.since_sample_ms < self.interval_ms {
+            return Ok(None);
+        }
+        self.since_sample_ms = 0;
+        if sensor.mode() == Mode::ActiveForce {
+            sensor.trigger_measurement()?;
+            self.since_trigger_ms = Some(0);
+            Ok(None)
+        } else {
+            self.read(sensor).map(Some)
+        }
+    }
+
+    fn read<I2C, E>(&self, sensor: &mut Veml6075<I2C>) -> Result<Measurement, Error<E>>
+    where
+        I2C: WriteRead<Error = E>,
+    {
+        let m = sensor.read()?;
+        if let Some(OnMeasurement(callback)) = self.on_measurement {
+            callback(&m);
+        }
+        Ok(m)
+    }
+}
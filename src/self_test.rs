@@ -0,0 +1,94 @@
+//! Built-in self-test
+use crate::regs::{BitFlags, Register, DEVICE_ID};
+use crate::{Error, RawMeasurement, Veml6075};
+use embedded_hal::blocking::{
+    delay::DelayMs,
+    i2c::{Write, WriteRead},
+};
+
+/// Maximum counts by which the visible and IR compensation may exceed the
+/// UVA or UVB reading in a plausible sample.
+pub const COMPENSATION_TOLERANCE: f32 = 10.0;
+
+/// Self-test report
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct SelfTestReport {
+    /// Device ID read
+    pub device_id: u16,
+    /// Whether the device ID is correct
+    pub device_id_ok: bool,
+    /// Whether the configuration read back matched the one written
+    pub config_ok: bool,
+    /// Raw sample acquired in active force mode, if the previous checks passed
+    pub sample: Option<RawMeasurement>,
+    /// Whether no channel of the sample was saturated and the visible and IR
+    /// compensation did not exceed the UVA and UVB readings by more than
+    /// [`COMPENSATION_TOLERANCE`](constant.COMPENSATION_TOLERANCE.html)
+    pub sample_ok: bool,
+}
+
+impl SelfTestReport {
+    /// Whether all checks passed.
+    pub fn passed(&self) -> bool {
+        self.device_id_ok && self.config_ok && self.sample_ok
+    }
+}
+
+impl<I2C, E> Veml6075<I2C>
+where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+{
+    /// Run a short power-on self-test.
+    ///
+    /// This checks the device ID, writes the configuration in active force
+    /// mode and reads it back, acquires a sample and checks it for
+    /// plausibility. The sensor configuration is restored afterwards. I²C
+    /// errors abort the test and are returned.
+    pub fn self_test<D: DelayMs<u16>>(
+        &mut self,
+        delay: &mut D,
+    ) -> Result<SelfTestReport, Error<E>> {
+        let device_id = self.read_device_id()?;
+        let mut report = SelfTestReport {
+            device_id,
+            device_id_ok: device_id == DEVICE_ID,
+            config_ok: false,
+            sample: None,
+            sample_ok: false,
+        };
+        if !report.device_id_ok {
+            return Ok(report);
+        }
+        let config = self.config;
+        let result = self.self_test_steps(delay, &mut report);
+        let restored = self.write_config(config);
+        result?;
+        restored?;
+        Ok(report)
+    }
+
+    fn self_test_steps<D: DelayMs<u16>>(
+        &mut self,
+        delay: &mut D,
+        report: &mut SelfTestReport,
+    ) -> Result<(), Error<E>> {
+        let test_config = (self.config & !BitFlags::SHUTDOWN) | BitFlags::UV_AF;
+        self.write_config(test_config)?;
+        let readback = self.read_register(Register::CONFIG)? as u8 & !BitFlags::UV_TRIG;
+        report.config_ok = readback == test_config;
+        if !report.config_ok {
+            return Ok(());
+        }
+        self.trigger_measurement()?;
+        delay.delay_ms(self.integration_time_ms());
+        let raw = self.read_raw()?;
+        let channels = [raw.uva, raw.uvb, raw.uvcomp1, raw.uvcomp2];
+        let (uva, uvb) = self.calibration.compensate_visible_ir(&raw);
+        report.sample = Some(raw);
+        report.sample_ok = !channels.contains(&0xFFFF)
+            && uva >= -COMPENSATION_TOLERANCE
+            && uvb >= -COMPENSATION_TOLERANCE;
+        Ok(())
+    }
+}
@@ -0,0 +1,41 @@
+//! SenML (RFC 8428) JSON format
+use crate::Measurement;
+use core::fmt::{Result, Write};
+
+impl Measurement {
+    /// Write the measurement as a SenML JSON pack.
+    ///
+    /// The pack contains the `uva`, `uvb` and `uvi` records under the given
+    /// base name, for example `urn:dev:mac:0024befffe804ff1:`. The base name
+    /// is written as is, so it must not contain characters which need escaping
+    /// in JSON. The optional time is in seconds since the UNIX epoch.
+    ///
+    /// The calibrated UVA and UVB readings are compensated sensor counts so
+    /// they use the `count` unit. The UV index has no SenML unit so its record
+    /// has no `u` field.
+    ///
+    /// For example:
+    /// `[{"bn":"veml6075:","bt":1700000000,"n":"uva","u":"count","v":123.4},{"n":"uvb","u":"count","v":98.7},{"n":"uvi","v":5.2}]`
+    pub fn write_senml<W: Write>(
+        &self,
+        writer: &mut W,
+        base_name: &str,
+        time: Option<u64>,
+    ) -> Result {
+        write!(writer, "[{{\"bn\":\"{}\",", base_name)?;
+        if let Some(time) = time {
+            write!(writer, "\"bt\":{},", time)?;
+        }
+        write!(
+            writer,
+            "\"n\":\"uva\",\"u\":\"count\",\"v\":{}}},",
+            self.uva
+        )?;
+        write!(
+            writer,
+            "{{\"n\":\"uvb\",\"u\":\"count\",\"v\":{}}},",
+            self.uvb
+        )?;
+        write!(writer, "{{\"n\":\"uvi\",\"v\":{}}}]", self.uv_index)
+    }
+}
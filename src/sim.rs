@@ -0,0 +1,407 @@
+//! Register-level simulator of the VEML6075 for host testing.
+//!
+//! [`FakeVeml6075`](struct.FakeVeml6075.html) implements the `embedded-hal`
+//! I²C traits so it can be passed to the driver in place of a real bus.
+//! It models the register map, the integration timing in both operating
+//! modes and the measurement trigger.
+//!
+//! Faults can be injected to test error-recovery paths: not acknowledged
+//! transactions, corrupted reads and stuck-at readings.
+//!
+//! The I²C traits are also implemented for `&FakeVeml6075` so that the
+//! simulated time can be advanced while the driver holds a reference to it.
+//!
+//! [`SyntheticSun`](struct.SyntheticSun.html) generates a deterministic
+//! input over a day. The simulator has no platform dependencies so it can
+//! also be compiled to `wasm32-unknown-unknown` to run the driver in a
+//! browser.
+//!
+//! ```
+//! use veml6075::{sim::FakeVeml6075, Calibration, RawMeasurement, Veml6075};
+//!
+//! let fake = FakeVeml6075::new();
+//! fake.set_input(RawMeasurement { uva: 1000, uvb: 1000, uvcomp1: 0, uvcomp2: 0 });
+//! let mut sensor = Veml6075::new(&fake, Calibration::default());
+//! sensor.enable().unwrap();
+//! // Nothing measured yet
+//! assert_eq!(0, sensor.read_uva_raw().unwrap());
+//! fake.advance(50);
+//! assert_eq!(500, sensor.read_uva_raw().unwrap());
+//! ```
+
+use crate::regs::{BitFlags, Register, DEVICE_ADDRESS, DEVICE_ID};
+use crate::{IntegrationTime, RawMeasurement};
+use core::cell::RefCell;
+use core::convert::TryFrom;
+use embedded_hal::blocking::i2c::{Write, WriteRead};
+
+/// Simulator error
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SimError {
+    /// The address was not acknowledged
+    AddressNack,
+    /// The register does not exist or is not writable
+    InvalidRegister(u8),
+    /// The transaction length is not valid
+    InvalidLength,
+    /// Injected transaction failure (not acknowledged)
+    Nack,
+}
+
+/// Simulated VEML6075 device.
+///
+/// The input counts set with [`set_input()`](#method.set_input) correspond
+/// to a 100 ms integration time with normal dynamic setting. They are scaled
+/// according to the configured integration time and dynamic setting and
+/// saturate at full scale.
+///
+/// Simulated time only advances through [`advance()`](#method.advance).
+#[derive(Debug, Default)]
+pub struct FakeVeml6075 {
+    state: RefCell<State>,
+}
+
+#[derive(Debug)]
+struct State {
+    config: u8,
+    input: RawMeasurement,
+    output: RawMeasurement,
+    elapsed_ms: u32,
+    converting: bool,
+    pending_nacks: u32,
+    pending_corruptions: u32,
+    corruption_mask: u16,
+    stuck_at: Option<u16>,
+}
+
+impl FakeVeml6075 {
+    /// Create a new simulated device in power-on state.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the simulated light input (counts at 100 ms, normal dynamic setting).
+    pub fn set_input(&self, input: RawMeasurement) {
+        self.state.borrow_mut().input = input;
+    }
+
+    /// Current configuration register value.
+    pub fn config(&self) -> u8 {
+        self.state.borrow().config
+    }
+
+    /// Whether a conversion is in progress.
+    pub fn is_converting(&self) -> bool {
+        self.state.borrow().converting
+    }
+
+    /// Current integration time in milliseconds.
+    pub fn integration_time_ms(&self) -> u32 {
+        self.state.borrow().integration_time_ms()
+    }
+
+    /// Advance the simulated time.
+    pub fn advance(&self, ms: u32) {
+        self.state.borrow_mut().advance(ms)
+    }
+
+    /// Make the next `count` transactions fail with `SimError::Nack`.
+    pub fn inject_nacks(&self, count: u32) {
+        self.state.borrow_mut().pending_nacks = count;
+    }
+
+    /// Corrupt the next `count` register reads by XOR-ing the value with `mask`.
+    pub fn inject_corrupted_reads(&self, count: u32, mask: u16) {
+        let mut state = self.state.borrow_mut();
+        state.pending_corruptions = count;
+        state.corruption_mask = mask;
+    }
+
+    /// Make all measurement registers report a fixed value until cleared with `None`.
+    pub fn set_stuck_at(&self, value: Option<u16>) {
+        self.state.borrow_mut().stuck_at = value;
+    }
+
+    fn write_impl(&self, address: u8, bytes: &[u8]) -> Result<(), SimError> {
+        self.state.borrow_mut().check_nack()?;
+        if address != DEVICE_ADDRESS {
+            return Err(SimError::AddressNack);
+        }
+        match bytes {
+            [Register::CONFIG, config, _] => {
+                self.state.borrow_mut().write_config(*config);
+                Ok(())
+            }
+            [register, _, _] => Err(SimError::InvalidRegister(*register)),
+            _ => Err(SimError::InvalidLength),
+        }
+    }
+
+    fn write_read_impl(
+        &self,
+        address: u8,
+        bytes: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<(), SimError> {
+        self.state.borrow_mut().check_nack()?;
+        if address != DEVICE_ADDRESS {
+            return Err(SimError::AddressNack);
+        }
+        match (bytes, buffer) {
+            ([register], [lsb, msb]) => {
+                let value = self.state.borrow_mut().read_register(*register)?;
+                *lsb = value as u8;
+                *msb = (value >> 8) as u8;
+                Ok(())
+            }
+            _ => Err(SimError::InvalidLength),
+        }
+    }
+}
+
+/// Milliseconds in a day
+const DAY_MS: u32 = 24 * 60 * 60 * 1000;
+
+/// Duration of a cloud in the synthetic sky, in milliseconds
+const CLOUD_MS: u32 = 10 * 60 * 1000;
+
+/// Deterministic synthetic sun.
+///
+/// Generates the simulator input at a time of day following a clear-sky
+/// profile between sunrise and sunset, optionally attenuated by
+/// pseudo-random clouds. The same parameters always produce the same input.
+///
+/// ```
+/// use veml6075::sim::{FakeVeml6075, SyntheticSun};
+///
+/// let sun = SyntheticSun::default();
+/// let fake = FakeVeml6075::new();
+/// fake.set_input(sun.input_at(13 * 3600 * 1000)); // Solar noon
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SyntheticSun {
+    /// Input at solar noon with a clear sky
+    pub noon: RawMeasurement,
+    /// Time of day of the sunrise, in milliseconds
+    pub sunrise_ms: u32,
+    /// Time of day of the sunset, in milliseconds
+    pub sunset_ms: u32,
+    /// Seed of the cloud generator. `None` for a clear sky.
+    pub clouds: Option<u32>,
+}
+
+impl Default for SyntheticSun {
+    /// Clear summer day from 06:00 to 20:00 reaching a UV index of about 9
+    /// with the default calibration.
+    fn default() -> Self {
+        SyntheticSun {
+            noon: RawMeasurement {
+                uva: 10000,
+                uvb: 7000,
+                uvcomp1: 1000,
+                uvcomp2: 500,
+            },
+            sunrise_ms: 6 * 3600 * 1000,
+            sunset_ms: 20 * 3600 * 1000,
+            clouds: None,
+        }
+    }
+}
+
+impl SyntheticSun {
+    /// Simulator input at a time of day in milliseconds.
+    ///
+    /// Times beyond a day wrap around so the simulated time can be used directly.
+    pub fn input_at(&self, time_ms: u32) -> RawMeasurement {
+        let time_ms = time_ms % DAY_MS;
+        if time_ms <= self.sunrise_ms || time_ms >= self.sunset_ms {
+            return RawMeasurement {
+                uva: 0,
+                uvb: 0,
+                uvcomp1: 0,
+                uvcomp2: 0,
+            };
+        }
+        // Position along the day from 0.0 at sunrise to 1.0 at sunset
+        let x = (time_ms - self.sunrise_ms) as f32 / (self.sunset_ms - self.sunrise_ms) as f32;
+        let elevation = 4.0 * x * (1.0 - x);
+        // UV rises much faster than visible light with the sun elevation.
+        let uv_factor = elevation * elevation * self.transmission(time_ms);
+        let visible_factor = elevation * self.transmission(time_ms);
+        let scale = |counts: u16, factor: f32| (f32::from(counts) * factor + 0.5) as u16;
+        RawMeasurement {
+            uva: scale(self.noon.uva, uv_factor),
+            uvb: scale(self.noon.uvb, uv_factor),
+            uvcomp1: scale(self.noon.uvcomp1, visible_factor),
+            uvcomp2: scale(self.noon.uvcomp2, visible_factor),
+        }
+    }
+
+    /// Cloud transmission between 0.3 and 1.0, interpolated between clouds.
+    fn transmission(&self, time_ms: u32) -> f32 {
+        let seed = match self.clouds {
+            Some(seed) => seed,
+            None => return 1.0,
+        };
+        let cloud = time_ms / CLOUD_MS;
+        let x = (time_ms % CLOUD_MS) as f32 / CLOUD_MS as f32;
+        let a = cloud_transmission(seed, cloud);
+        let b = cloud_transmission(seed, cloud + 1);
+        a + (b - a) * x
+    }
+}
+
+fn cloud_transmission(seed: u32, cloud: u32) -> f32 {
+    // xorshift32 hash of the seed and cloud number
+    let mut x = seed ^ cloud.wrapping_mul(0x9E37_79B9) ^ 0x2545_F491;
+    x ^= x << 13;
+    x ^= x >> 17;
+    x ^= x << 5;
+    0.3 + 0.7 * (x >> 8) as f32 / (1 << 24) as f32
+}
+
+impl Default for State {
+    fn default() -> Self {
+        let zero = RawMeasurement {
+            uva: 0,
+            uvb: 0,
+            uvcomp1: 0,
+            uvcomp2: 0,
+        };
+        State {
+            config: BitFlags::SHUTDOWN,
+            input: zero,
+            output: zero,
+            elapsed_ms: 0,
+            converting: false,
+            pending_nacks: 0,
+            pending_corruptions: 0,
+            corruption_mask: 0,
+            stuck_at: None,
+        }
+    }
+}
+
+impl State {
+    fn integration_time_ms(&self) -> u32 {
+        let it = IntegrationTime::try_from(
+            (self.config & BitFlags::UV_IT_MASK) >> BitFlags::UV_IT_SHIFT,
+        )
+        .unwrap_or(IntegrationTime::Ms800);
+        50 << u8::from(it)
+    }
+
+    fn advance(&mut self, ms: u32) {
+        if !self.converting {
+            return;
+        }
+        self.elapsed_ms += ms;
+        let it = self.integration_time_ms();
+        while self.converting && self.elapsed_ms >= it {
+            self.elapsed_ms -= it;
+            self.update_output();
+            if self.config & BitFlags::UV_AF != 0 {
+                self.converting = false;
+                self.elapsed_ms = 0;
+            }
+        }
+    }
+
+    fn update_output(&mut self) {
+        let it = self.integration_time_ms();
+        let divider = if self.config & BitFlags::HD != 0 {
+            200
+        } else {
+            100
+        };
+        let scale = |counts: u16| {
+            let value = u32::from(counts) * it / divider;
+            if value > 0xFFFF {
+                0xFFFF
+            } else {
+                value as u16
+            }
+        };
+        self.output = RawMeasurement {
+            uva: scale(self.input.uva),
+            uvb: scale(self.input.uvb),
+            uvcomp1: scale(self.input.uvcomp1),
+            uvcomp2: scale(self.input.uvcomp2),
+        };
+    }
+
+    fn write_config(&mut self, config: u8) {
+        let trigger = config & BitFlags::UV_TRIG != 0;
+        self.config = config & !BitFlags::UV_TRIG;
+        self.elapsed_ms = 0;
+        let enabled = self.config & BitFlags::SHUTDOWN == 0;
+        self.converting = if self.config & BitFlags::UV_AF != 0 {
+            enabled && (trigger || self.converting)
+        } else {
+            enabled
+        };
+    }
+
+    fn check_nack(&mut self) -> Result<(), SimError> {
+        if self.pending_nacks > 0 {
+            self.pending_nacks -= 1;
+            Err(SimError::Nack)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn read_register(&mut self, register: u8) -> Result<u16, SimError> {
+        let value = match (register, self.stuck_at) {
+            (Register::CONFIG, _) => u16::from(self.config),
+            (Register::DEVICE_ID, _) => DEVICE_ID,
+            (Register::UVA, Some(value))
+            | (Register::UVB, Some(value))
+            | (Register::UVCOMP1, Some(value))
+            | (Register::UVCOMP2, Some(value)) => value,
+            (Register::UVA, None) => self.output.uva,
+            (Register::UVB, None) => self.output.uvb,
+            (Register::UVCOMP1, None) => self.output.uvcomp1,
+            (Register::UVCOMP2, None) => self.output.uvcomp2,
+            _ => return Err(SimError::InvalidRegister(register)),
+        };
+        if self.pending_corruptions > 0 {
+            self.pending_corruptions -= 1;
+            Ok(value ^ self.corruption_mask)
+        } else {
+            Ok(value)
+        }
+    }
+}
+
+impl Write for FakeVeml6075 {
+    type Error = SimError;
+
+    fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), SimError> {
+        self.write_impl(address, bytes)
+    }
+}
+
+impl WriteRead for FakeVeml6075 {
+    type Error = SimError;
+
+    fn write_read(&mut self, address: u8, bytes: &[u8], buffer: &mut [u8]) -> Result<(), SimError> {
+        self.write_read_impl(address, bytes, buffer)
+    }
+}
+
+impl Write for &FakeVeml6075 {
+    type Error = SimError;
+
+    fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), SimError> {
+        self.write_impl(address, bytes)
+    }
+}
+
+impl WriteRead for &FakeVeml6075 {
+    type Error = SimError;
+
+    fn write_read(&mut self, address: u8, bytes: &[u8], buffer: &mut [u8]) -> Result<(), SimError> {
+        self.write_read_impl(address, bytes, buffer)
+    }
+}
@@ -0,0 +1,98 @@
+//! Measurement sinks and push pipeline
+use crate::{Clock, Error, Measurement, Scheduler, TimestampedMeasurement, Veml6075};
+use core::fmt;
+use embedded_hal::blocking::i2c::{Write, WriteRead};
+
+/// Consumer of measurements, for example a display, a radio or a log.
+///
+/// This is implemented for closures taking a `&TimestampedMeasurement`.
+pub trait MeasurementSink {
+    /// Process a new measurement.
+    fn push(&mut self, measurement: &TimestampedMeasurement);
+}
+
+impl<F> MeasurementSink for F
+where
+    F: FnMut(&TimestampedMeasurement),
+{
+    fn push(&mut self, measurement: &TimestampedMeasurement) {
+        self(measurement)
+    }
+}
+
+/// Measurement callback of a [`Sampler`](struct.Sampler.html) or a
+/// [`Scheduler`](struct.Scheduler.html).
+#[derive(Clone, Copy)]
+pub(crate) struct OnMeasurement(pub(crate) fn(&Measurement));
+
+impl fmt::Debug for OnMeasurement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("OnMeasurement")
+    }
+}
+
+/// Pipeline reading measurements on schedule and pushing them to several sinks.
+///
+/// The timing is handled by a [`Scheduler`](struct.Scheduler.html) so the
+/// pump is fed with the time elapsed since the last call to
+/// [`tick()`](#method.tick).
+///
+/// ```
+/// use embedded_hal::blocking::i2c::{Write, WriteRead};
+/// use veml6075::{Clock, Pump, TimestampedMeasurement, Veml6075};
+///
+/// // Called from a periodic 1 s timer.
+/// fn on_timer<I2C, E, C: Clock>(sensor: &mut Veml6075<I2C>, clock: &C, pump: &mut Pump)
+/// where
+///     I2C: Write<Error = E> + WriteRead<Error = E>,
+/// {
+///     let mut display = |m: &TimestampedMeasurement| {
+///         // show m.measurement.uv_index
+///     };
+///     let mut radio = |m: &TimestampedMeasurement| {
+///         // transmit m
+///     };
+///     let _ = pump.tick(sensor, clock, 1000, &mut [&mut display, &mut radio]);
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Pump {
+    scheduler: Scheduler,
+}
+
+impl Pump {
+    /// Create a new pump with the given sample interval in milliseconds.
+    pub fn new(interval_ms: u32) -> Self {
+        Pump {
+            scheduler: Scheduler::new(interval_ms),
+        }
+    }
+
+    /// Advance the schedule and push the measurement to all sinks once available.
+    ///
+    /// Returns the measurement pushed, if any.
+    pub fn tick<I2C, E, C>(
+        &mut self,
+        sensor: &mut Veml6075<I2C>,
+        clock: &C,
+        elapsed_ms: u32,
+        sinks: &mut [&mut dyn MeasurementSink],
+    ) -> Result<Option<TimestampedMeasurement>, Error<E>>
+    where
+        I2C: Write<Error = E> + WriteRead<Error = E>,
+        C: Clock,
+    {
+        let measurement = match self.scheduler.tick(sensor, elapsed_ms)? {
+            Some(m) => m,
+            None => return Ok(None),
+        };
+        let m = TimestampedMeasurement {
+            at: clock.now_ms(),
+            measurement,
+        };
+        for sink in sinks.iter_mut() {
+            sink.push(&m);
+        }
+        Ok(Some(m))
+    }
+}
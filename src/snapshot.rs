@@ -0,0 +1,130 @@
+//! Configuration snapshots
+use crate::regs::BitFlags;
+use crate::{Calibration, DynamicSetting, Error, IntegrationTime, Mode, Veml6075};
+use embedded_hal::blocking::i2c::Write;
+
+/// Snapshot of the device configuration
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ConfigSnapshot {
+    /// Integration time
+    pub integration_time: IntegrationTime,
+    /// Dynamic setting
+    pub dynamic_setting: DynamicSetting,
+    /// Operating mode
+    pub mode: Mode,
+    /// Whether the sensor is enabled (not shut down)
+    pub enabled: bool,
+}
+
+impl Default for ConfigSnapshot {
+    /// Enabled, continuous mode, 100 ms integration time and normal dynamic setting.
+    fn default() -> Self {
+        ConfigSnapshot {
+            integration_time: IntegrationTime::Ms100,
+            dynamic_setting: DynamicSetting::Normal,
+            mode: Mode::Continuous,
+            enabled: true,
+        }
+    }
+}
+
+impl ConfigSnapshot {
+    fn config(&self) -> u8 {
+        let mut config = u8::from(self.integration_time) << BitFlags::UV_IT_SHIFT;
+        if self.dynamic_setting == DynamicSetting::High {
+            config |= BitFlags::HD;
+        }
+        if self.mode == Mode::ActiveForce {
+            config |= BitFlags::UV_AF;
+        }
+        if !self.enabled {
+            config |= BitFlags::SHUTDOWN;
+        }
+        config
+    }
+}
+
+impl<I2C> Veml6075<I2C> {
+    /// Capture the current configuration.
+    ///
+    /// This does not access the device.
+    pub fn snapshot(&self) -> ConfigSnapshot {
+        ConfigSnapshot {
+            integration_time: self.integration_time(),
+            dynamic_setting: self.dynamic_setting(),
+            mode: self.mode(),
+            enabled: self.config & BitFlags::SHUTDOWN == 0,
+        }
+    }
+}
+
+impl<I2C, E> Veml6075<I2C>
+where
+    I2C: Write<Error = E>,
+{
+    /// Apply a configuration snapshot with a single register write.
+    ///
+    /// This is useful to restore the configuration after temporarily
+    /// changing it.
+    pub fn restore(&mut self, snapshot: ConfigSnapshot) -> Result<(), Error<E>> {
+        let was_shut_down = self.config & BitFlags::SHUTDOWN != 0;
+        self.write_config(snapshot.config())?;
+        if was_shut_down && snapshot.enabled {
+            self.quality.after_enable = true;
+        }
+        Ok(())
+    }
+}
+
+/// Driver state preserved across a deep sleep
+///
+/// See [`suspend()`](struct.Veml6075.html#method.suspend).
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SuspendToken {
+    /// I²C device address
+    pub address: u8,
+    /// Configuration before suspending
+    pub config: ConfigSnapshot,
+    /// Calibration coefficients
+    pub calibration: Calibration,
+}
+
+impl<I2C, E> Veml6075<I2C>
+where
+    I2C: Write<Error = E>,
+{
+    /// Shut the sensor down and return the driver state for a deep sleep.
+    ///
+    /// The token can be stored in memory preserved during the sleep, for
+    /// example RTC memory, and passed to [`resume()`](#method.resume) on wake.
+    pub fn suspend(&mut self) -> Result<SuspendToken, Error<E>> {
+        let token = SuspendToken {
+            address: self.address,
+            config: self.snapshot(),
+            calibration: self.calibration,
+        };
+        self.disable()?;
+        Ok(token)
+    }
+
+    /// Recreate the driver after a deep sleep and restore the configuration
+    /// from before [`suspend()`](#method.suspend).
+    ///
+    /// The retry policy, bus observer and calibration registry are not part
+    /// of the token and have their default values.
+    ///
+    /// Returns `Error::InvalidCalibration` if the calibration in the token
+    /// is not valid. See [`Calibration::is_valid()`](struct.Calibration.html#method.is_valid).
+    pub fn resume(i2c: I2C, token: SuspendToken) -> Result<Self, Error<E>> {
+        if !token.calibration.is_valid() {
+            return Err(Error::InvalidCalibration);
+        }
+        let mut sensor = Self::new_with_address(i2c, token.address, token.calibration);
+        sensor.restore(token.config)?;
+        Ok(sensor)
+    }
+}
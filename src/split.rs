@@ -0,0 +1,139 @@
+//! Driver split into configuration and data halves
+use crate::{
+    BusStats, Calibration, DynamicSetting, Error, IntegrationTime, Measurement, Mode,
+    RawMeasurement, Veml6075,
+};
+use embedded_hal::blocking::i2c::{Write, WriteRead};
+
+/// Half of a split driver which can only change the configuration.
+///
+/// See [`split()`](struct.Veml6075.html#method.split).
+#[derive(Debug)]
+pub struct Configurator<I2C> {
+    sensor: Veml6075<I2C>,
+}
+
+/// Half of a split driver which can only read the measurement data.
+///
+/// See [`split()`](struct.Veml6075.html#method.split).
+#[derive(Debug)]
+pub struct Reader<I2C> {
+    sensor: Veml6075<I2C>,
+}
+
+impl<I2C, E> Veml6075<I2C>
+where
+    I2C: Write<Error = E>,
+{
+    /// Split the driver into a configuration half and a data reading half.
+    ///
+    /// This allows one task to configure the sensor while another one reads
+    /// the measurements. The reader uses its own I²C bus instance, for
+    /// example another proxy from a bus sharing crate. The calibration,
+    /// retry policy and bus observer are passed on to the reader.
+    pub fn split<I2C2>(self, reader_i2c: I2C2) -> (Configurator<I2C>, Reader<I2C2>)
+    where
+        I2C2: WriteRead,
+    {
+        let reader = Veml6075 {
+            i2c: reader_i2c,
+            address: self.address,
+            config: self.config,
+            calibration: self.calibration,
+            prepared: self.prepared,
+            cached: None,
+            retry_policy: self.retry_policy,
+            stats: BusStats::default(),
+            bus_observer: self.bus_observer,
+            quality: self.quality,
+            double_read: self.double_read,
+            read_mismatches: 0,
+            settling: self.settling,
+            calibrations: self.calibrations,
+        };
+        (Configurator { sensor: self }, Reader { sensor: reader })
+    }
+}
+
+impl<I2C, E> Configurator<I2C>
+where
+    I2C: Write<Error = E>,
+{
+    /// Join the halves again, return the driver and the reader I²C bus instance.
+    ///
+    /// The calibration of the reader is kept.
+    pub fn join<I2C2>(self, reader: Reader<I2C2>) -> (Veml6075<I2C>, I2C2) {
+        let mut sensor = self.sensor;
+        sensor.calibration = reader.sensor.calibration;
+        sensor.prepared = reader.sensor.prepared;
+        sensor.cached = None;
+        (sensor, reader.sensor.i2c)
+    }
+
+    /// Enable the sensor.
+    pub fn enable(&mut self) -> Result<(), Error<E>> {
+        self.sensor.enable()
+    }
+
+    /// Disable the sensor (shutdown).
+    pub fn disable(&mut self) -> Result<(), Error<E>> {
+        self.sensor.disable()
+    }
+
+    /// Set operating mode
+    pub fn set_mode(&mut self, mode: Mode) -> Result<(), Error<E>> {
+        self.sensor.set_mode(mode)
+    }
+
+    /// Trigger a measurement when on active force (one-shot) mode.
+    pub fn trigger_measurement(&mut self) -> Result<(), Error<E>> {
+        self.sensor.trigger_measurement()
+    }
+
+    /// Set the integration time.
+    pub fn set_integration_time(&mut self, it: IntegrationTime) -> Result<(), Error<E>> {
+        self.sensor.set_integration_time(it)
+    }
+
+    /// Set the dynamic setting.
+    pub fn set_dynamic_setting(&mut self, ds: DynamicSetting) -> Result<(), Error<E>> {
+        self.sensor.set_dynamic_setting(ds)
+    }
+}
+
+impl<I2C, E> Reader<I2C>
+where
+    I2C: WriteRead<Error = E>,
+{
+    /// Set the calibration coefficients.
+    ///
+    /// Returns `Error::InvalidCalibration` if the coefficients are not valid.
+    pub fn set_calibration(&mut self, calibration: Calibration) -> Result<(), Error<E>> {
+        if !calibration.is_valid() {
+            return Err(Error::InvalidCalibration);
+        }
+        self.sensor.calibration = calibration;
+        self.sensor.prepared = calibration.into();
+        Ok(())
+    }
+
+    /// Get the calibration coefficients.
+    pub fn calibration(&self) -> Calibration {
+        self.sensor.calibration
+    }
+
+    /// Read the sensor data and calculate calibrated reading values.
+    pub fn read(&mut self) -> Result<Measurement, Error<E>> {
+        self.sensor.read()
+    }
+
+    /// Read the raw UVA, UVB, UVcomp1 and UVcomp2 sensor data.
+    pub fn read_raw(&mut self) -> Result<RawMeasurement, Error<E>> {
+        self.sensor.read_raw()
+    }
+
+    /// Read the device ID
+    pub fn read_device_id(&mut self) -> Result<u16, Error<E>> {
+        self.sensor.read_device_id()
+    }
+}
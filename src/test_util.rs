@@ -0,0 +1,51 @@
+//! Utilities to write expectation-based tests against this driver.
+//!
+//! These are based on [`embedded-hal-mock`](https://docs.rs/embedded-hal-mock).
+//!
+//! ```
+//! use veml6075::test_util::{config_write, destroy, new, Register};
+//!
+//! let mut sensor = new(&[config_write(0)]);
+//! sensor.enable().unwrap();
+//! destroy(sensor);
+//! ```
+
+use crate::{Calibration, RawMeasurement, Veml6075};
+pub use embedded_hal_mock::i2c::{Mock as I2cMock, Transaction as I2cTrans};
+use std::vec;
+
+pub use crate::regs::{Register, DEVICE_ADDRESS, DEVICE_ID};
+
+/// Create a driver instance using a mock I²C bus expecting the given transactions.
+pub fn new(transactions: &[I2cTrans]) -> Veml6075<I2cMock> {
+    Veml6075::new(I2cMock::new(transactions), Calibration::default())
+}
+
+/// Destroy the driver instance and check that all expected transactions happened.
+pub fn destroy(sensor: Veml6075<I2cMock>) {
+    sensor.destroy().done();
+}
+
+/// Expected write of the configuration register.
+pub fn config_write(config: u8) -> I2cTrans {
+    I2cTrans::write(DEVICE_ADDRESS, vec![Register::CONFIG, config, 0])
+}
+
+/// Expected read of a register returning the given value.
+pub fn register_read(register: u8, value: u16) -> I2cTrans {
+    I2cTrans::write_read(
+        DEVICE_ADDRESS,
+        vec![register],
+        vec![value as u8, (value >> 8) as u8],
+    )
+}
+
+/// Expected reads of all measurement registers as done by `read()` and `read_raw()`.
+pub fn raw_measurement_read(raw: &RawMeasurement) -> [I2cTrans; 4] {
+    [
+        register_read(Register::UVA, raw.uva),
+        register_read(Register::UVB, raw.uvb),
+        register_read(Register::UVCOMP1, raw.uvcomp1),
+        register_read(Register::UVCOMP2, raw.uvcomp2),
+    ]
+}
@@ -0,0 +1,111 @@
+//! UV index threshold crossing detection
+/// Maximum number of levels in [`Thresholds`](struct.Thresholds.html).
+pub const MAX_THRESHOLD_LEVELS: usize = 8;
+
+/// Capacity of the event queue of a [`Sampler`](struct.Sampler.html).
+///
+/// The oldest events are discarded when the queue is full.
+pub const EVENT_QUEUE_LEN: usize = 8;
+
+/// UV index threshold crossing event
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Event {
+    /// The UV index rose to or above the level.
+    CrossedUp(f32),
+    /// The UV index fell below the level.
+    CrossedDown(f32),
+}
+
+/// UV index levels to detect crossings of.
+///
+/// The UV index starts at 0 so the first measurement already reports the
+/// levels it is at or above as crossed up.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Thresholds {
+    levels: [f32; MAX_THRESHOLD_LEVELS],
+    len: usize,
+    uv_index: f32,
+}
+
+impl Thresholds {
+    /// Create thresholds for the given UV index levels in ascending order.
+    ///
+    /// Only the first [`MAX_THRESHOLD_LEVELS`](constant.MAX_THRESHOLD_LEVELS.html)
+    /// levels are used.
+    pub fn new(levels: &[f32]) -> Self {
+        let len = levels.len().min(MAX_THRESHOLD_LEVELS);
+        let mut t = Thresholds {
+            levels: [0.0; MAX_THRESHOLD_LEVELS],
+            len,
+            uv_index: 0.0,
+        };
+        t.levels[..len].copy_from_slice(&levels[..len]);
+        t
+    }
+
+    /// Configured levels.
+    pub fn levels(&self) -> &[f32] {
+        &self.levels[..self.len]
+    }
+
+    /// Update with a new UV index and call `f` for every level crossed.
+    ///
+    /// Crossings up are reported in ascending order and crossings down in
+    /// descending order.
+    pub fn update<F: FnMut(Event)>(&mut self, uv_index: f32, mut f: F) {
+        let previous = self.uv_index;
+        self.uv_index = uv_index;
+        if uv_index >= previous {
+            for &level in self.levels() {
+                if previous < level && uv_index >= level {
+                    f(Event::CrossedUp(level));
+                }
+            }
+        } else {
+            for &level in self.levels().iter().rev() {
+                if previous >= level && uv_index < level {
+                    f(Event::CrossedDown(level));
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct EventQueue {
+    events: [Option<Event>; EVENT_QUEUE_LEN],
+    head: usize,
+    len: usize,
+}
+
+impl EventQueue {
+    pub(crate) fn new() -> Self {
+        EventQueue {
+            events: [None; EVENT_QUEUE_LEN],
+            head: 0,
+            len: 0,
+        }
+    }
+
+    pub(crate) fn push(&mut self, event: Event) {
+        let tail = (self.head + self.len) % EVENT_QUEUE_LEN;
+        self.events[tail] = Some(event);
+        if self.len == EVENT_QUEUE_LEN {
+            self.head = (self.head + 1) % EVENT_QUEUE_LEN;
+        } else {
+            self.len += 1;
+        }
+    }
+
+    pub(crate) fn pop(&mut self) -> Option<Event> {
+        if self.len == 0 {
+            return None;
+        }
+        let event = self.events[self.head].take();
+        self.head = (self.head + 1) % EVENT_QUEUE_LEN;
+        self.len -= 1;
+        event
+    }
+}
@@ -0,0 +1,58 @@
+//! I²C adapter using transactions
+use embedded_hal::blocking::i2c::{Operation, Transactional, Write, WriteRead};
+
+/// I²C bus adapter performing each access as a single transaction.
+///
+/// The register reads (write of the register address followed by a read)
+/// are executed with `Transactional::exec()` instead of `WriteRead`. This
+/// lets DMA-capable HALs coalesce the operations and keeps the bus locked
+/// during the whole access on multi-master buses.
+///
+/// ```no_run
+/// use linux_embedded_hal::I2cdev;
+/// use veml6075::{Calibration, TransactionI2c, Veml6075};
+///
+/// let dev = I2cdev::new("/dev/i2c-1").unwrap();
+/// let mut sensor = Veml6075::new(TransactionI2c::new(dev), Calibration::default());
+/// let m = sensor.read().unwrap();
+/// ```
+#[derive(Debug, Default)]
+pub struct TransactionI2c<I2C> {
+    i2c: I2C,
+}
+
+impl<I2C> TransactionI2c<I2C> {
+    /// Create a new adapter.
+    pub fn new(i2c: I2C) -> Self {
+        TransactionI2c { i2c }
+    }
+
+    /// Destroy the adapter, return the I²C bus instance.
+    pub fn destroy(self) -> I2C {
+        self.i2c
+    }
+}
+
+impl<I2C: Transactional> Write for TransactionI2c<I2C> {
+    type Error = I2C::Error;
+
+    fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.i2c.exec(address, &mut [Operation::Write(bytes)])
+    }
+}
+
+impl<I2C: Transactional> WriteRead for TransactionI2c<I2C> {
+    type Error = I2C::Error;
+
+    fn write_read(
+        &mut self,
+        address: u8,
+        bytes: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.i2c.exec(
+            address,
+            &mut [Operation::Write(bytes), Operation::Read(buffer)],
+        )
+    }
+}
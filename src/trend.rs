@@ -0,0 +1,107 @@
+//! UV index trend detection.
+
+/// Maximum number of samples in the trend window.
+pub const MAX_WINDOW: usize = 32;
+
+/// Short-term UV index trend
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Trend {
+    /// The UV index is increasing.
+    Rising,
+    /// The UV index is decreasing.
+    Falling,
+    /// The UV index is stable.
+    Steady,
+}
+
+/// UV index trend analyzer.
+///
+/// The trend is classified from the least-squares slope of the UV index
+/// over the last samples, in UV index units per hour.
+///
+/// ```
+/// use veml6075::trend::{Trend, TrendAnalyzer};
+///
+/// let mut trend = TrendAnalyzer::new(10, 1.0);
+/// trend.update(0, 4.0);
+/// // 10 minutes later
+/// assert_eq!(Some(Trend::Rising), trend.update(600_000, 4.5));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrendAnalyzer {
+    samples: [(u64, f32); MAX_WINDOW],
+    window: usize,
+    len: usize,
+    next: usize,
+    threshold: f32,
+}
+
+impl TrendAnalyzer {
+    /// Create a new analyzer.
+    ///
+    /// The window is the number of samples considered, between 2 and
+    /// [`MAX_WINDOW`](constant.MAX_WINDOW.html). The trend is steady while
+    /// the magnitude of the slope is below the threshold, in UV index units
+    /// per hour.
+    pub fn new(window: usize, threshold: f32) -> Self {
+        TrendAnalyzer {
+            samples: [(0, 0.0); MAX_WINDOW],
+            window: window.clamp(2, MAX_WINDOW),
+            len: 0,
+            next: 0,
+            threshold,
+        }
+    }
+
+    /// Add a UV index sample taken at the given time in milliseconds and
+    /// classify the trend.
+    ///
+    /// Returns `None` until two samples at different times are available.
+    pub fn update(&mut self, at_ms: u64, uv_index: f32) -> Option<Trend> {
+        self.samples[self.next] = (at_ms, uv_index);
+        self.next = (self.next + 1) % self.window;
+        self.len = (self.len + 1).min(self.window);
+        self.trend()
+    }
+
+    /// Classify the trend of the samples in the window.
+    pub fn trend(&self) -> Option<Trend> {
+        let slope = self.slope()?;
+        Some(if slope >= self.threshold {
+            Trend::Rising
+        } else if slope <= -self.threshold {
+            Trend::Falling
+        } else {
+            Trend::Steady
+        })
+    }
+
+    /// Slope of the UV index in the window, in UV index units per hour.
+    pub fn slope(&self) -> Option<f32> {
+        let samples = &self.samples[..self.len];
+        let t0 = samples.iter().map(|s| s.0).min()?;
+        let n = self.len as f32;
+        let hours = |t: u64| (t - t0) as f32 / 3_600_000.0;
+        let mean_t = samples.iter().map(|s| hours(s.0)).sum::<f32>() / n;
+        let mean_uvi = samples.iter().map(|s| s.1).sum::<f32>() / n;
+        let (mut cov, mut var) = (0.0, 0.0);
+        for &(t, uvi) in samples {
+            let dt = hours(t) - mean_t;
+            cov += dt * (uvi - mean_uvi);
+            var += dt * dt;
+        }
+        if var > 0.0 {
+            Some(cov / var)
+        } else {
+            None
+        }
+    }
+
+    /// Discard all samples.
+    pub fn reset(&mut self) {
+        self.len = 0;
+        self.next = 0;
+    }
+}
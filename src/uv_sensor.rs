@@ -0,0 +1,40 @@
+//! Generic UV sensor interface
+use crate::{Error, Measurement, Veml6075};
+use embedded_hal::blocking::i2c::{Write, WriteRead};
+
+/// Generic UV sensor interface.
+///
+/// This allows applications and test doubles to be generic over the
+/// UV sensor in use.
+pub trait UvSensor {
+    /// Error type
+    type Error;
+
+    /// Enable the sensor.
+    fn enable(&mut self) -> Result<(), Self::Error>;
+
+    /// Disable the sensor (shutdown).
+    fn disable(&mut self) -> Result<(), Self::Error>;
+
+    /// Read a calibrated UV measurement.
+    fn read_uv(&mut self) -> Result<Measurement, Self::Error>;
+}
+
+impl<I2C, E> UvSensor for Veml6075<I2C>
+where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+{
+    type Error = Error<E>;
+
+    fn enable(&mut self) -> Result<(), Self::Error> {
+        Veml6075::enable(self)
+    }
+
+    fn disable(&mut self) -> Result<(), Self::Error> {
+        Veml6075::disable(self)
+    }
+
+    fn read_uv(&mut self) -> Result<Measurement, Self::Error> {
+        self.read()
+    }
+}
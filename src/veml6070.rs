@@ -0,0 +1,155 @@
+//! VEML6070 UVA light sensor support
+use crate::{Error, Measurement, UvSensor};
+use embedded_hal::blocking::i2c::{Read, Write};
+
+struct Address;
+impl Address {
+    const COMMAND: u8 = 0x38;
+    const DATA_LSB: u8 = 0x38;
+    const DATA_MSB: u8 = 0x39;
+}
+
+struct BitFlags;
+impl BitFlags {
+    const SHUTDOWN: u8 = 0b0000_0001;
+    const RESERVED: u8 = 0b0000_0010;
+}
+
+/// UVI per count at 1T integration time with the recommended 270 kΩ RSET.
+const UVI_PER_COUNT: f32 = 1.0 / 187.0;
+
+/// VEML6070 integration time (in multiples of T, which is defined by RSET)
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Veml6070IntegrationTime {
+    /// 1/2 T
+    Half,
+    /// 1 T (default)
+    T1,
+    /// 2 T
+    T2,
+    /// 4 T
+    T4,
+}
+
+/// VEML6070 UVA light sensor device driver.
+///
+/// This sensor only measures UVA so the UVB value of the measurements
+/// is always zero. The UV index is estimated from the UVA reading assuming
+/// the recommended 270 kΩ RSET resistor.
+#[derive(Debug)]
+pub struct Veml6070<I2C> {
+    i2c: I2C,
+    command: u8,
+    it: Veml6070IntegrationTime,
+}
+
+impl<I2C, E> Veml6070<I2C>
+where
+    I2C: Write<Error = E>,
+{
+    /// Create new instance of the Veml6070 device.
+    pub fn new(i2c: I2C) -> Self {
+        Veml6070 {
+            i2c,
+            command: 0b0000_0111, // 1T, shutdown
+            it: Veml6070IntegrationTime::T1,
+        }
+    }
+
+    /// Destroy driver instance, return I²C bus instance.
+    pub fn destroy(self) -> I2C {
+        self.i2c
+    }
+
+    /// Enable the sensor.
+    pub fn enable(&mut self) -> Result<(), Error<E>> {
+        let command = self.command;
+        self.write_command(command & !BitFlags::SHUTDOWN)
+    }
+
+    /// Disable the sensor (shutdown).
+    pub fn disable(&mut self) -> Result<(), Error<E>> {
+        let command = self.command;
+        self.write_command(command | BitFlags::SHUTDOWN)
+    }
+
+    /// Set the integration time.
+    pub fn set_integration_time(&mut self, it: Veml6070IntegrationTime) -> Result<(), Error<E>> {
+        let command = self.command & 0b1111_0011;
+        let command = match it {
+            Veml6070IntegrationTime::Half => command,
+            Veml6070IntegrationTime::T1 => command | 1 << 2,
+            Veml6070IntegrationTime::T2 => command | 2 << 2,
+            Veml6070IntegrationTime::T4 => command | 3 << 2,
+        };
+        self.write_command(command)?;
+        self.it = it;
+        Ok(())
+    }
+
+    fn write_command(&mut self, command: u8) -> Result<(), Error<E>> {
+        self.i2c
+            .write(Address::COMMAND, &[command | BitFlags::RESERVED])
+            .map_err(Error::I2C)?;
+        self.command = command;
+        Ok(())
+    }
+}
+
+impl<I2C, E> Veml6070<I2C>
+where
+    I2C: Read<Error = E>,
+{
+    /// Read the raw UVA sensor data.
+    pub fn read_uva_raw(&mut self) -> Result<u16, Error<E>> {
+        let mut msb = [0];
+        let mut lsb = [0];
+        self.i2c
+            .read(Address::DATA_MSB, &mut msb)
+            .map_err(Error::I2C)?;
+        self.i2c
+            .read(Address::DATA_LSB, &mut lsb)
+            .map_err(Error::I2C)?;
+        Ok(u16::from(msb[0]) << 8 | u16::from(lsb[0]))
+    }
+
+    /// Read the UVA sensor data and estimate the UV index.
+    ///
+    /// The UVA value is given in counts normalized to 1 T integration time,
+    /// so it does not depend on the configured integration time.
+    pub fn read(&mut self) -> Result<Measurement, Error<E>> {
+        let counts = f32::from(self.read_uva_raw()?);
+        let scale = match self.it {
+            Veml6070IntegrationTime::Half => 0.5,
+            Veml6070IntegrationTime::T1 => 1.0,
+            Veml6070IntegrationTime::T2 => 2.0,
+            Veml6070IntegrationTime::T4 => 4.0,
+        };
+        let uva = counts / scale;
+        Ok(Measurement {
+            uva,
+            uvb: 0.0,
+            uv_index: uva * UVI_PER_COUNT,
+        })
+    }
+}
+
+impl<I2C, E> UvSensor for Veml6070<I2C>
+where
+    I2C: Write<Error = E> + Read<Error = E>,
+{
+    type Error = Error<E>;
+
+    fn enable(&mut self) -> Result<(), Self::Error> {
+        Veml6070::enable(self)
+    }
+
+    fn disable(&mut self) -> Result<(), Self::Error> {
+        Veml6070::disable(self)
+    }
+
+    fn read_uv(&mut self) -> Result<Measurement, Self::Error> {
+        self.read()
+    }
+}
@@ -0,0 +1,78 @@
+//! Compact binary encoding of measurements.
+//!
+//! The layouts are fixed and use little-endian byte order so that readings
+//! can be transmitted over low-bandwidth links without ad-hoc packing.
+//!
+//! [`Measurement`] (12 bytes):
+//!
+//! | Bytes  | Field      | Type  |
+//! |--------|------------|-------|
+//! | 0..4   | `uva`      | `f32` |
+//! | 4..8   | `uvb`      | `f32` |
+//! | 8..12  | `uv_index` | `f32` |
+//!
+//! [`RawMeasurement`] (8 bytes):
+//!
+//! | Bytes  | Field      | Type  |
+//! |--------|------------|-------|
+//! | 0..2   | `uva`      | `u16` |
+//! | 2..4   | `uvb`      | `u16` |
+//! | 4..6   | `uvcomp1`  | `u16` |
+//! | 6..8   | `uvcomp2`  | `u16` |
+//!
+//! [`Measurement`]: ../struct.Measurement.html
+//! [`RawMeasurement`]: ../struct.RawMeasurement.html
+
+use crate::{Measurement, RawMeasurement};
+
+/// Encoded size of a [`Measurement`](../struct.Measurement.html) in bytes.
+pub const MEASUREMENT_LEN: usize = 12;
+
+/// Encoded size of a [`RawMeasurement`](../struct.RawMeasurement.html) in bytes.
+pub const RAW_MEASUREMENT_LEN: usize = 8;
+
+/// Encode a calibrated measurement.
+pub fn encode_measurement(m: &Measurement) -> [u8; MEASUREMENT_LEN] {
+    let mut data = [0; MEASUREMENT_LEN];
+    data[0..4].copy_from_slice(&m.uva.to_le_bytes());
+    data[4..8].copy_from_slice(&m.uvb.to_le_bytes());
+    data[8..12].copy_from_slice(&m.uv_index.to_le_bytes());
+    data
+}
+
+/// Decode a calibrated measurement.
+pub fn decode_measurement(data: &[u8; MEASUREMENT_LEN]) -> Measurement {
+    Measurement {
+        uva: read_f32(&data[0..4]),
+        uvb: read_f32(&data[4..8]),
+        uv_index: read_f32(&data[8..12]),
+    }
+}
+
+/// Encode a raw measurement.
+pub fn encode_raw_measurement(m: &RawMeasurement) -> [u8; RAW_MEASUREMENT_LEN] {
+    let mut data = [0; RAW_MEASUREMENT_LEN];
+    data[0..2].copy_from_slice(&m.uva.to_le_bytes());
+    data[2..4].copy_from_slice(&m.uvb.to_le_bytes());
+    data[4..6].copy_from_slice(&m.uvcomp1.to_le_bytes());
+    data[6..8].copy_from_slice(&m.uvcomp2.to_le_bytes());
+    data
+}
+
+/// Decode a raw measurement.
+pub fn decode_raw_measurement(data: &[u8; RAW_MEASUREMENT_LEN]) -> RawMeasurement {
+    RawMeasurement {
+        uva: read_u16(&data[0..2]),
+        uvb: read_u16(&data[2..4]),
+        uvcomp1: read_u16(&data[4..6]),
+        uvcomp2: read_u16(&data[6..8]),
+    }
+}
+
+fn read_u16(data: &[u8]) -> u16 {
+    u16::from_le_bytes([data[0], data[1]])
+}
+
+fn read_f32(data: &[u8]) -> f32 {
+    f32::from_le_bytes([data[0], data[1], data[2], data[3]])
+}
@@ -0,0 +1,146 @@
+//! Golden-sample calibration procedure
+use crate::{Calibration, Measurement, RawMeasurement};
+
+/// Calibration wizard state
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum WizardState {
+    /// Collecting samples
+    Collecting {
+        /// Number of samples collected so far
+        collected: u16,
+        /// Number of samples required
+        required: u16,
+    },
+    /// Calibration finished
+    Done(Calibration),
+    /// The samples do not allow computing a calibration
+    /// (for example, no UV light was present)
+    Failed,
+}
+
+/// Golden-sample calibration procedure.
+///
+/// The sensor being calibrated and a reference (golden) unit are exposed to
+/// the same light. For each sample, the raw measurement of the sensor being
+/// calibrated and the calibrated measurement of the reference unit are added
+/// to the wizard. Once the required number of samples has been collected,
+/// the UVA and UVB responsivities are computed in two least squares fits:
+///
+/// 1. Each channel is scaled so that the compensated counts of the sensor
+///    match the ones of the reference unit. This corrects the gain
+///    differences between the UVA and UVB channels of both units.
+/// 2. Both responsivities are then scaled by a common factor so that the
+///    UV index reported by the sensor matches the one of the reference unit.
+///
+/// If the reference unit uses other responsivities than the base
+/// calibration, the UV index still matches as long as their UVA to UVB
+/// ratio is the same, as it is for the same light spectrum.
+///
+/// The wizard holds no references, so it can be stored and resumed later.
+///
+/// ```
+/// use veml6075::{Calibration, CalibrationWizard, Measurement, RawMeasurement, WizardState};
+///
+/// let mut wizard = CalibrationWizard::new(Calibration::default(), 1);
+/// let dut = RawMeasurement { uva: 1000, uvb: 1000, uvcomp1: 0, uvcomp2: 0 };
+/// let reference = Measurement::new(1100.0, 900.0, 1.97);
+/// if let WizardState::Done(calibration) = wizard.add_sample(&dut, &reference) {
+///     // use calibration
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CalibrationWizard {
+    base: Calibration,
+    required: u16,
+    collected: u16,
+    uva_cross: f32,
+    uva_square: f32,
+    uvb_cross: f32,
+    uvb_square: f32,
+    uva_uvi_cross: f32,
+    uvb_uvi_cross: f32,
+    uva_uva: f32,
+    uva_uvb: f32,
+    uvb_uvb: f32,
+}
+
+impl CalibrationWizard {
+    /// Create a new wizard requiring the given number of samples.
+    ///
+    /// The base calibration provides the compensation coefficients, which
+    /// are kept as they are.
+    pub fn new(base: Calibration, required: u16) -> Self {
+        CalibrationWizard {
+            base,
+            required,
+            collected: 0,
+            uva_cross: 0.0,
+            uva_square: 0.0,
+            uvb_cross: 0.0,
+            uvb_square: 0.0,
+            uva_uvi_cross: 0.0,
+            uvb_uvi_cross: 0.0,
+            uva_uva: 0.0,
+            uva_uvb: 0.0,
+            uvb_uvb: 0.0,
+        }
+    }
+
+    /// Add a sample pair and return the new state.
+    ///
+    /// Samples added after the calibration is done are ignored.
+    pub fn add_sample(&mut self, raw: &RawMeasurement, reference: &Measurement) -> WizardState {
+        if self.collected < self.required {
+            let m = self.base.compensate(raw);
+            self.uva_cross += m.uva * reference.uva;
+            self.uva_square += m.uva * m.uva;
+            self.uvb_cross += m.uvb * reference.uvb;
+            self.uvb_square += m.uvb * m.uvb;
+            // UV index contributions of each channel with the base responsivities
+            let uva = m.uva * self.base.uva_responsivity / 2.0;
+            let uvb = m.uvb * self.base.uvb_responsivity / 2.0;
+            self.uva_uvi_cross += uva * reference.uv_index;
+            self.uvb_uvi_cross += uvb * reference.uv_index;
+            self.uva_uva += uva * uva;
+            self.uva_uvb += uva * uvb;
+            self.uvb_uvb += uvb * uvb;
+            self.collected += 1;
+        }
+        self.state()
+    }
+
+    /// Current state.
+    pub fn state(&self) -> WizardState {
+        if self.collected < self.required {
+            return WizardState::Collecting {
+                collected: self.collected,
+                required: self.required,
+            };
+        }
+        if self.uva_square <= 0.0 || self.uvb_square <= 0.0 {
+            return WizardState::Failed;
+        }
+        let uva_gain = self.uva_cross / self.uva_square;
+        let uvb_gain = self.uvb_cross / self.uvb_square;
+        let uvi_cross = uva_gain * self.uva_uvi_cross + uvb_gain * self.uvb_uvi_cross;
+        let uvi_square = uva_gain * uva_gain * self.uva_uva
+            + 2.0 * uva_gain * uvb_gain * self.uva_uvb
+            + uvb_gain * uvb_gain * self.uvb_uvb;
+        if uvi_square <= 0.0 || uvi_cross <= 0.0 {
+            return WizardState::Failed;
+        }
+        let scale = uvi_cross / uvi_square;
+        let mut calibration = self.base;
+        calibration.uva_responsivity *= uva_gain * scale;
+        calibration.uvb_responsivity *= uvb_gain * scale;
+        WizardState::Done(calibration)
+    }
+
+    /// Discard the collected samples and start again.
+    pub fn reset(&mut self) {
+        *self = CalibrationWizard::new(self.base, self.required);
+    }
+}
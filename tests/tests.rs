@@ -1,21 +1,30 @@
-use embedded_hal_mock::i2c::{Mock as I2cMock, Transaction as I2cTrans};
+use embedded_hal::blocking::i2c::{Operation, Transactional, Write};
+use embedded_hal_mock::{
+    delay::MockNoop,
+    i2c::{Mock as I2cMock, Transaction as I2cTrans},
+    MockError,
+};
+use std::convert::TryFrom;
+use std::io::ErrorKind;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Mutex;
 use veml6075::{
-    Calibration, DynamicSetting as DS, IntegrationTime as IT, Measurement, Mode, Veml6075,
+    bthome, check_headroom, lpp, migrate_calibration_blob, mqtt, wire, AutoExposure, BlobError,
+    BusStats, Calibration, CalibrationWizard, Clock, ConfigSnapshot, CoverType, Direction,
+    DynamicSetting as DS, Error, Event, FullMeasurement, IntegrationTime as IT, LowPowerSampler,
+    Measurement, MeasurementFlags, Mode, MuxedVeml6075, Oversampler, PollState, Poller, Pump,
+    RawMeasurement, RawSum, RetryPolicy, Sampler, Scheduler, ShutdownOnDrop, SuspendToken,
+    Thresholds, TimestampedMeasurement, TransactionI2c, UvIndexClass, UvSensor, Veml6075,
+    Veml6075Array, WizardState, CALIBRATION_BLOB_LEN, CALIBRATION_BLOB_VERSION, DEFAULT_COVER,
 };
 
-const DEVICE_ADDRESS: u8 = 0x10;
-struct Register;
-impl Register {
-    const CONFIG: u8 = 0x00;
-    const UVA: u8 = 0x07;
-    const UVB: u8 = 0x09;
-    const UVCOMP1: u8 = 0x0A;
-    const UVCOMP2: u8 = 0x0B;
-    const DEVICE_ID: u8 = 0x0C;
-}
+use veml6075::regs::{BitFlags, Register, DEVICE_ADDRESS};
 
 pub fn new(transactions: &[I2cTrans]) -> Veml6075<I2cMock> {
-    Veml6075::new(I2cMock::new(transactions), Calibration::default())
+    Veml6075::new(
+        I2cMock::new(transactions),
+        Calibration::for_cover(CoverType::None),
+    )
 }
 
 pub fn destroy(sensor: Veml6075<I2cMock>) {
@@ -82,7 +91,9 @@ fn can_read_calibrated() {
         I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVCOMP2], vec![0xD7, 0x02]),
     ];
     let mut dev = new(&transactions);
-    let Measurement { uva, uvb, uv_index } = dev.read().unwrap();
+    let Measurement {
+        uva, uvb, uv_index, ..
+    } = dev.read().unwrap();
 
     let expected_uva = 3967.0 - 2.22 * 1007.0 - 1.33 * 727.0;
     assert!(uva - 0.5 < expected_uva);
@@ -98,37 +109,2187 @@ fn can_read_calibrated() {
 }
 
 #[test]
-fn calibration_default() {
-    let c = Calibration {
-        uva_visible: 2.22,
-        uva_ir: 1.33,
-        uvb_visible: 2.95,
-        uvb_ir: 1.74,
-        uva_responsivity: 0.001_461,
-        uvb_responsivity: 0.002_591,
+fn can_read_full() {
+    let transactions = [
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVA], vec![0xE8, 0x03]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVB], vec![0xD0, 0x07]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVCOMP1], vec![100, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVCOMP2], vec![200, 0]),
+    ];
+    let mut dev = new(&transactions);
+    dev.set_calibration(
+        Calibration::new(1.0, 2.0, 3.0, 4.0, 0.5, 0.25).with_transmission(0.5, 0.5),
+    )
+    .unwrap();
+    let full = dev.read_full().unwrap();
+    assert_eq!(1000, full.raw.uva);
+    assert_eq!(2000, full.raw.uvb);
+    assert_eq!(500.0, full.uva_compensated);
+    assert_eq!(900.0, full.uvb_compensated);
+    assert_eq!(1000.0, full.measurement.uva);
+    assert_eq!(1800.0, full.measurement.uvb);
+    assert_eq!(475.0, full.measurement.uv_index);
+    destroy(dev);
+}
+
+#[test]
+fn uncompensated_read_skips_compensation_channels() {
+    let transactions = [
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVA], vec![0x7F, 0x0F]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVB], vec![0xBA, 0x16]),
+    ];
+    let mut dev = new(&transactions);
+    dev.set_calibration(Calibration::new(0.0, 0.0, 0.0, 0.0, 1.0, 1.0))
+        .unwrap();
+    let m = dev.read().unwrap();
+    assert_eq!(3967.0, m.uva);
+    assert_eq!(5818.0, m.uvb);
+    destroy(dev);
+}
+
+#[test]
+fn can_read_flagged() {
+    let read = |uva: u8, uvb| {
+        vec![
+            I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVA], vec![uva, uva]),
+            I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVB], vec![uvb, 0]),
+            I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVCOMP1], vec![0, 0]),
+            I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVCOMP2], vec![0, 0]),
+        ]
     };
-    assert_eq!(c, Calibration::default());
+    let mut transactions = vec![I2cTrans::write(
+        DEVICE_ADDRESS,
+        vec![Register::CONFIG, 0, 0],
+    )];
+    transactions.extend(read(0xFF, 10));
+    transactions.extend(read(0xFF, 10));
+    transactions.extend(read(1, 2));
+    let mut dev = new(&transactions);
+    dev.set_noise_floor(300);
+    dev.enable().unwrap();
+    let m = dev.read_flagged().unwrap();
+    assert_eq!(
+        MeasurementFlags::SATURATED | MeasurementFlags::FIRST_AFTER_ENABLE,
+        m.flags
+    );
+    let m = dev.read_flagged().unwrap();
+    assert_eq!(
+        MeasurementFlags::SATURATED | MeasurementFlags::STALE,
+        m.flags
+    );
+    let m = dev.read_flagged().unwrap();
+    assert_eq!(MeasurementFlags::BELOW_NOISE_FLOOR, m.flags);
+    destroy(dev);
+}
+
+#[test]
+fn can_estimate_noise() {
+    let mut transactions = Vec::new();
+    for uva in [2, 4, 4, 4, 5, 5, 7, 9].iter() {
+        transactions.push(I2cTrans::write_read(
+            DEVICE_ADDRESS,
+            vec![Register::UVA],
+            vec![*uva, 0],
+        ));
+        transactions.push(I2cTrans::write_read(
+            DEVICE_ADDRESS,
+            vec![Register::UVB],
+            vec![3, 0],
+        ));
+        transactions.push(I2cTrans::write_read(
+            DEVICE_ADDRESS,
+            vec![Register::UVCOMP1],
+            vec![0, 0],
+        ));
+        transactions.push(I2cTrans::write_read(
+            DEVICE_ADDRESS,
+            vec![Register::UVCOMP2],
+            vec![0, 0],
+        ));
+    }
+    let mut dev = new(&transactions);
+    let noise = dev.estimate_noise(8, &mut MockNoop::new()).unwrap();
+    assert_eq!(5.0, noise.uva.mean);
+    assert!((noise.uva.std_dev - 2.138).abs() < 1e-3);
+    assert_eq!(3.0, noise.uvb.mean);
+    assert_eq!(0.0, noise.uvb.std_dev);
+    let snr = noise.snr(&RawMeasurement {
+        uva: 9,
+        uvb: 3,
+        uvcomp1: 0,
+        uvcomp2: 0,
+    });
+    assert!((snr.uva - 1.871).abs() < 1e-3);
+    assert_eq!(0.0, snr.uvb);
+    assert_eq!(f32::INFINITY, noise.uvb.snr(4));
+    destroy(dev);
+}
+
+#[test]
+fn can_self_test() {
+    let transactions = [
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::DEVICE_ID], vec![0x26, 0]),
+        I2cTrans::write(DEVICE_ADDRESS, vec![Register::CONFIG, 0b0000_0010, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::CONFIG], vec![0b0000_0010, 0]),
+        I2cTrans::write(DEVICE_ADDRESS, vec![Register::CONFIG, 0b0000_0110, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVA], vec![100, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVB], vec![100, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVCOMP1], vec![200, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVCOMP2], vec![10, 0]),
+        I2cTrans::write(DEVICE_ADDRESS, vec![Register::CONFIG, 0b0000_0001, 0]),
+    ];
+    let mut dev = new(&transactions);
+    let report = dev.self_test(&mut MockNoop::new()).unwrap();
+    assert!(report.device_id_ok);
+    assert!(report.config_ok);
+    assert_eq!(200, report.sample.unwrap().uvcomp1);
+    // UVA compensated: 100 - 2.22 * 200 - 1.33 * 10 < -10
+    assert!(!report.sample_ok);
+    assert!(!report.passed());
+    destroy(dev);
+}
+
+#[test]
+fn default_calibration_uses_default_cover() {
+    assert_eq!(
+        Calibration::for_cover(DEFAULT_COVER),
+        Calibration::default()
+    );
+}
+
+#[cfg(feature = "cover-pmma")]
+#[test]
+fn pmma_cover_takes_precedence() {
+    assert_eq!(CoverType::Pmma, DEFAULT_COVER);
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn can_deserialize_calibration_without_optional_coefficients() {
+    let json = r#"{"uva_visible":2.22,"uva_ir":1.33,"uvb_visible":2.95,"uvb_ir":1.74,
+        "uva_responsivity":0.001461,"uvb_responsivity":0.002591}"#;
+    let (c, _): (Calibration, _) = serde_json_core::from_str(json).unwrap();
+    assert_eq!(Calibration::for_cover(CoverType::None), c);
+}
+
+#[test]
+fn calibration_default() {
+    let c = Calibration::new(2.22, 1.33, 2.95, 1.74, 0.001_461, 0.002_591);
+    assert_eq!(c, Calibration::for_cover(CoverType::None));
+    assert_eq!(2.22, c.uva_visible());
+    assert_eq!(1.33, c.uva_ir());
+    assert_eq!(2.95, c.uvb_visible());
+    assert_eq!(1.74, c.uvb_ir());
+    assert_eq!(0.001_461, c.uva_responsivity());
+    assert_eq!(0.002_591, c.uvb_responsivity());
+    assert_eq!(1.0, c.angular_correction());
+    assert_eq!(1.0, c.uva_transmission());
+    assert_eq!(1.0, c.uvb_transmission());
+    assert_eq!(1.0, c.uva_gain());
+    assert_eq!(1.0, c.uvb_gain());
 }
 
 #[test]
 fn measurement_can_store() {
-    let m = Measurement {
-        uva: 1.1,
-        uvb: 2.2,
-        uv_index: 3.3,
-    };
-    assert!(m.uva - 0.5 < 1.1);
-    assert!(m.uva + 0.5 > 1.1);
-    assert!(m.uvb - 0.5 < 2.2);
-    assert!(m.uvb + 0.5 > 2.2);
-    assert!(m.uv_index - 0.5 < 3.3);
-    assert!(m.uv_index + 0.5 > 3.3);
-    assert_eq!(
-        m,
-        Measurement {
-            uva: 1.1,
-            uvb: 2.2,
-            uv_index: 3.3,
+    let m = Measurement::new(1.1, 2.2, 3.3);
+    assert!(m.uva() - 0.5 < 1.1);
+    assert!(m.uva() + 0.5 > 1.1);
+    assert!(m.uvb() - 0.5 < 2.2);
+    assert!(m.uvb() + 0.5 > 2.2);
+    assert!(m.uv_index() - 0.5 < 3.3);
+    assert!(m.uv_index() + 0.5 > 3.3);
+    assert_eq!(m, Measurement::new(1.1, 2.2, 3.3));
+}
+
+#[test]
+fn full_measurement_can_store() {
+    let raw = RawMeasurement {
+        uva: 1,
+        uvb: 2,
+        uvcomp1: 3,
+        uvcomp2: 4,
+    };
+    let m = Measurement::new(1.1, 2.2, 3.3);
+    let full = FullMeasurement::new(raw, 0.5, 1.5, m);
+    assert_eq!(raw, full.raw());
+    assert_eq!(0.5, full.uva_compensated());
+    assert_eq!(1.5, full.uvb_compensated());
+    assert_eq!(m, full.measurement());
+}
+
+macro_rules! field_conv_test {
+    ($name:ident, $type:ident, $($variant:expr => $value:expr),*; $invalid:expr) => {
+        #[test]
+        fn $name() {
+            $(
+                assert_eq!($value, u8::from($variant));
+                assert_eq!(Ok($variant), $type::try_from($value));
+            )*
+            assert_eq!(Err(()), $type::try_from($invalid));
+        }
+    };
+}
+
+field_conv_test!(it_field_conv, IT, IT::Ms50 => 0, IT::Ms100 => 1, IT::Ms200 => 2,
+                 IT::Ms400 => 3, IT::Ms800 => 4; 5);
+field_conv_test!(ds_field_conv, DS, DS::Normal => 0, DS::High => 1; 2);
+field_conv_test!(mode_field_conv, Mode, Mode::Continuous => 0, Mode::ActiveForce => 1; 2);
+
+#[test]
+fn can_classify_uv_index() {
+    assert_eq!(UvIndexClass::Low, UvIndexClass::from_uv_index(0.0));
+    assert_eq!(UvIndexClass::Low, UvIndexClass::from_uv_index(2.4));
+    assert_eq!(UvIndexClass::Moderate, UvIndexClass::from_uv_index(2.5));
+    assert_eq!(UvIndexClass::Moderate, UvIndexClass::from_uv_index(5.4));
+    assert_eq!(UvIndexClass::High, UvIndexClass::from_uv_index(5.5));
+    assert_eq!(UvIndexClass::High, UvIndexClass::from_uv_index(7.4));
+    assert_eq!(UvIndexClass::VeryHigh, UvIndexClass::from_uv_index(7.5));
+    assert_eq!(UvIndexClass::VeryHigh, UvIndexClass::from_uv_index(10.4));
+    assert_eq!(UvIndexClass::Extreme, UvIndexClass::from_uv_index(10.5));
+    assert_eq!(UvIndexClass::Extreme, UvIndexClass::from_uv_index(15.0));
+    assert_eq!(UvIndexClass::Low, UvIndexClass::from_uv_index(f32::NAN));
+}
+
+#[test]
+fn can_display_measurement() {
+    let m = Measurement::new(123.44, 98.66, 6.21);
+    assert_eq!(UvIndexClass::High, m.uv_index_class());
+    assert_eq!("UVA 123.4 UVB 98.7 UVI 6.2 (High)", format!("{}", m));
+    assert_eq!("Very high", format!("{}", UvIndexClass::VeryHigh));
+}
+
+#[test]
+fn can_read_raw() {
+    let transactions = [
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVA], vec![0x7F, 0x0F]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVB], vec![0xBA, 0x16]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVCOMP1], vec![0xEF, 0x03]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVCOMP2], vec![0xD7, 0x02]),
+    ];
+    let mut dev = new(&transactions);
+    let raw = dev.read_raw().unwrap();
+    assert_eq!(
+        RawMeasurement {
+            uva: 3967,
+            uvb: 5818,
+            uvcomp1: 1007,
+            uvcomp2: 727
+        },
+        raw
+    );
+    destroy(dev);
+}
+
+#[test]
+fn can_use_alternate_address() {
+    let transactions = [
+        I2cTrans::write(0x20, vec![Register::CONFIG, 0, 0]),
+        I2cTrans::write_read(0x20, vec![Register::DEVICE_ID], vec![0x26, 0]),
+    ];
+    let mut dev =
+        Veml6075::new_with_address(I2cMock::new(&transactions), 0x20, Calibration::default());
+    dev.enable().unwrap();
+    assert_eq!(0x26, dev.read_device_id().unwrap());
+    destroy(dev);
+}
+
+#[derive(Default)]
+struct RecordingTransactional {
+    operations: Vec<(u8, Vec<u8>, usize)>,
+}
+
+impl Transactional for RecordingTransactional {
+    type Error = ();
+
+    fn exec(&mut self, address: u8, operations: &mut [Operation]) -> Result<(), ()> {
+        let (write, read) = match operations {
+            [Operation::Write(bytes)] => (bytes.to_vec(), None),
+            [Operation::Write(bytes), Operation::Read(buffer)] => (bytes.to_vec(), Some(buffer)),
+            _ => return Err(()),
+        };
+        let read_len = read.as_ref().map_or(0, |buffer| buffer.len());
+        if let Some(buffer) = read {
+            buffer.copy_from_slice(&[0x26, 0]);
         }
+        self.operations.push((address, write, read_len));
+        Ok(())
+    }
+}
+
+#[test]
+fn can_use_transactions() {
+    let i2c = TransactionI2c::new(RecordingTransactional::default());
+    let mut dev = Veml6075::new(i2c, Calibration::default());
+    dev.enable().unwrap();
+    assert_eq!(0x26, dev.read_device_id().unwrap());
+    let operations = dev.destroy().destroy().operations;
+    assert_eq!(
+        vec![
+            (DEVICE_ADDRESS, vec![Register::CONFIG, 0, 0], 0),
+            (DEVICE_ADDRESS, vec![Register::DEVICE_ID], 2),
+        ],
+        operations
+    );
+}
+
+#[test]
+fn can_borrow_bus() {
+    let transactions = [
+        I2cTrans::write(DEVICE_ADDRESS, vec![Register::CONFIG, 0, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::CONFIG], vec![0, 0]),
+        I2cTrans::write(DEVICE_ADDRESS, vec![Register::CONFIG, 1, 0]),
+    ];
+    let mut i2c = I2cMock::new(&transactions);
+    Veml6075::new_borrowed(&mut i2c, Calibration::default())
+        .enable()
+        .unwrap();
+    let mut sensor = Veml6075::new_borrowed(&mut i2c, Calibration::default());
+    sensor.load_config().unwrap();
+    sensor.disable().unwrap();
+    i2c.done();
+}
+
+#[test]
+fn can_split() {
+    let config_transactions = [
+        I2cTrans::write(DEVICE_ADDRESS, vec![Register::CONFIG, 0b0000_0011, 0]),
+        I2cTrans::write(DEVICE_ADDRESS, vec![Register::CONFIG, 0b0000_0111, 0]),
+    ];
+    let read_transactions = [
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVA], vec![100, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVB], vec![200, 0]),
+    ];
+    let dev = new(&config_transactions);
+    let (mut configurator, mut reader) = dev.split(I2cMock::new(&read_transactions));
+    reader
+        .set_calibration(Calibration::new(0.0, 0.0, 0.0, 0.0, 1.0, 1.0))
+        .unwrap();
+    configurator.set_mode(Mode::ActiveForce).unwrap();
+    configurator.trigger_measurement().unwrap();
+    let m = reader.read().unwrap();
+    assert_eq!(100.0, m.uva);
+    assert_eq!(200.0, m.uvb);
+    let (dev, mut reader_i2c) = configurator.join(reader);
+    assert_eq!(0.0, dev.calibration().uva_visible);
+    reader_i2c.done();
+    destroy(dev);
+}
+
+#[cfg(feature = "linux")]
+#[test]
+fn open_fails_for_missing_device() {
+    match Veml6075::open("/dev/i2c-does-not-exist") {
+        Err(Error::I2C(_)) => (),
+        _ => panic!("Error expected"),
+    }
+}
+
+#[test]
+fn can_get_integration_time() {
+    let transactions = [I2cTrans::write(
+        DEVICE_ADDRESS,
+        vec![Register::CONFIG, 0b0100_0001, 0],
+    )];
+    let mut dev = new(&transactions);
+    assert_eq!(IT::Ms50, dev.integration_time());
+    dev.set_integration_time(IT::Ms800).unwrap();
+    assert_eq!(IT::Ms800, dev.integration_time());
+    destroy(dev);
+}
+
+#[test]
+fn register_map_is_public() {
+    assert_eq!(0x10, veml6075::DEVICE_ADDRESS);
+    let config = BitFlags::UV_AF | BitFlags::SHUTDOWN | (2 << BitFlags::UV_IT_SHIFT);
+    let transactions = [
+        I2cTrans::write(DEVICE_ADDRESS, vec![Register::CONFIG, 0b0000_0011, 0]),
+        I2cTrans::write(DEVICE_ADDRESS, vec![Register::CONFIG, config, 0]),
+    ];
+    let mut dev = new(&transactions);
+    dev.set_mode(Mode::ActiveForce).unwrap();
+    dev.set_integration_time(IT::Ms200).unwrap();
+    destroy(dev);
+}
+
+#[test]
+fn can_read_raw_pair() {
+    let transactions = [
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVA], vec![0x7F, 0x0F]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVB], vec![0xBA, 0x16]),
+    ];
+    let mut dev = new(&transactions);
+    assert_eq!((3967, 5818), dev.read_raw_pair().unwrap());
+    destroy(dev);
+}
+
+#[test]
+fn can_encode_lpp() {
+    let m = Measurement::new(-3.0, 70000.0, -0.456);
+    assert_eq!(
+        [255, 101, 0, 0, 0, 101, 0xFF, 0xFF, 1, 2, 0xFF, 0xD2],
+        lpp::encode(&m, 255)
+    );
+}
+
+#[test]
+fn can_encode_bthome() {
+    let m = Measurement::new(-3.0, 70000.0, 30.0);
+    let ad = bthome::encode(&m, 1);
+    assert_eq!([0, 0, 0x3D, 0xFF, 0xFF, 0x46, 255], ad[8..]);
+}
+
+#[test]
+fn wire_measurement_roundtrip() {
+    let m = Measurement::new(1.5, -2.25, 3.0);
+    let data = wire::encode_measurement(&m);
+    assert_eq!([0, 0, 0xC0, 0x3F, 0, 0, 0x10, 0xC0, 0, 0, 0x40, 0x40], data);
+    assert_eq!(m, wire::decode_measurement(&data));
+}
+
+#[test]
+fn wire_raw_measurement_roundtrip() {
+    let m = RawMeasurement {
+        uva: 0x0102,
+        uvb: 0x0304,
+        uvcomp1: 0x0506,
+        uvcomp2: 0x0708,
+    };
+    let data = wire::encode_raw_measurement(&m);
+    assert_eq!([2, 1, 4, 3, 6, 5, 8, 7], data);
+    assert_eq!(m, wire::decode_raw_measurement(&data));
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn can_write_json() {
+    let m = Measurement::new(1.5, 2.25, 3.0);
+    let mut buffer = [0; 64];
+    let len = m.write_json(&mut buffer).unwrap();
+    assert_eq!(
+        "{\"uva\":1.5,\"uvb\":2.25,\"uv_index\":3.0}",
+        core::str::from_utf8(&buffer[..len]).unwrap()
+    );
+    assert!(m.write_json(&mut buffer[..8]).is_err());
+}
+
+#[test]
+fn measurement_arithmetic() {
+    let a = Measurement::new(1.0, 2.0, 3.0);
+    let b = Measurement::new(3.0, 6.0, 1.0);
+    let sum = Measurement::new(4.0, 8.0, 4.0);
+    assert_eq!(sum, a + b);
+    assert_eq!(a, sum - b);
+    assert_eq!(Measurement::new(2.0, 4.0, 2.0), sum / 2.0);
+    assert_eq!(Some(sum / 2.0), Measurement::mean(vec![a, b]));
+    assert_eq!(Some(a), Measurement::mean(Some(a)));
+    assert_eq!(None, Measurement::mean(None));
+}
+
+#[test]
+fn oversampler_averages() {
+    let raw = |uva, uvb| RawMeasurement {
+        uva,
+        uvb,
+        uvcomp1: 0,
+        uvcomp2: 0,
+    };
+    let mut oversampler = Oversampler::new(4);
+    assert_eq!(None, oversampler.add(&raw(0xFFFF, 1)));
+    assert_eq!(None, oversampler.add(&raw(0xFFFF, 1)));
+    assert_eq!(None, oversampler.add(&raw(0xFFFF, 2)));
+    let sum = oversampler.add(&raw(0xFFFF, 2)).unwrap();
+    assert_eq!(4 * 0xFFFF, sum.uva);
+    assert_eq!(6, sum.uvb);
+    assert_eq!(4, sum.count);
+    assert_eq!(raw(0xFFFF, 2), sum.average());
+    let m = sum.compensate(&Calibration::new(0.0, 0.0, 0.0, 0.0, 1.0, 1.0));
+    assert_eq!(1.5, m.uvb);
+    assert_eq!(None, oversampler.add(&raw(1, 1)));
+    oversampler.reset();
+    assert_eq!(None, oversampler.add(&raw(1, 1)));
+    assert_eq!(raw(0, 0), RawSum::default().average());
+}
+
+#[test]
+fn can_write_csv() {
+    let m = Measurement::new(1.5, 2.25, 3.0);
+    let mut s = String::new();
+    Measurement::write_csv_header(&mut s).unwrap();
+    m.write_csv(&mut s, 1000).unwrap();
+    assert_eq!("timestamp,uva,uvb,uvi\n1000,1.5,2.25,3\n", s);
+}
+
+#[test]
+fn can_write_prometheus() {
+    let m = Measurement::new(1.5, 2.25, 3.0);
+    let mut s = String::new();
+    m.write_prometheus(&mut s).unwrap();
+    assert_eq!(
+        "# HELP veml6075_uva Compensated UVA counts.\n\
+         # TYPE veml6075_uva gauge\n\
+         veml6075_uva 1.5\n\
+         # HELP veml6075_uvb Compensated UVB counts.\n\
+         # TYPE veml6075_uvb gauge\n\
+         veml6075_uvb 2.25\n\
+         # HELP veml6075_uvi UV index.\n\
+         # TYPE veml6075_uvi gauge\n\
+         veml6075_uvi 3\n",
+        s
+    );
+}
+
+#[test]
+fn can_write_senml() {
+    let m = Measurement::new(1.5, 2.25, 3.0);
+    let mut s = String::new();
+    m.write_senml(&mut s, "veml6075:", Some(1000)).unwrap();
+    assert_eq!(
+        "[{\"bn\":\"veml6075:\",\"bt\":1000,\"n\":\"uva\",\"u\":\"count\",\"v\":1.5},\
+         {\"n\":\"uvb\",\"u\":\"count\",\"v\":2.25},{\"n\":\"uvi\",\"v\":3}]",
+        s
+    );
+    s.clear();
+    m.write_senml(&mut s, "x", None).unwrap();
+    assert!(s.starts_with("[{\"bn\":\"x\",\"n\":\"uva\""));
+}
+
+#[test]
+fn can_write_line_protocol() {
+    let m = Measurement::new(1.5, 2.25, 3.0);
+    let mut buffer = [0; 80];
+    let len = m
+        .to_line_protocol("uv light", &[("site", "a=b,c")], Some(1000), &mut buffer)
+        .unwrap();
+    assert_eq!(
+        b"uv\\ light,site=a\\=b\\,c uva=1.5,uvb=2.25,uvi=3 1000\n",
+        &buffer[..len]
+    );
+    let len = m.to_line_protocol("uv", &[], None, &mut buffer).unwrap();
+    assert_eq!(b"uv uva=1.5,uvb=2.25,uvi=3\n", &buffer[..len]);
+    assert!(m.to_line_protocol("uv", &[], None, &mut [0; 10]).is_err());
+}
+
+#[test]
+fn can_write_mqtt_discovery() {
+    let device = mqtt::Device {
+        node_id: "garden",
+        name: "Garden \"UV\"",
+        state_topic: "veml6075/garden",
+    };
+    let mut s = String::new();
+    device
+        .write_discovery_topic(mqtt::Entity::UvIndex, &mut s)
+        .unwrap();
+    assert_eq!("homeassistant/sensor/garden/uvi/config", s);
+    s.clear();
+    device
+        .write_discovery_config(mqtt::Entity::UvIndex, &mut s)
+        .unwrap();
+    assert_eq!(
+        "{\"name\":\"UV index\",\"unique_id\":\"garden_uvi\",\
+         \"state_topic\":\"veml6075/garden\",\"value_template\":\"{{ value_json.uvi }}\",\
+         \"state_class\":\"measurement\",\"icon\":\"mdi:weather-sunny-alert\",\
+         \"device\":{\"identifiers\":[\"garden\"],\"name\":\"Garden \\\"UV\\\"\",\
+         \"manufacturer\":\"Vishay\",\"model\":\"VEML6075\"}}",
+        s
+    );
+}
+
+#[test]
+fn can_generate_mqtt_discovery() {
+    let device = mqtt::Device {
+        node_id: "garden",
+        name: "Garden",
+        state_topic: "veml6075/garden",
+    };
+    let (mut topic, mut payload) = ([0; 64], [0; 512]);
+    let mut messages = Vec::new();
+    device
+        .discovery(0x26, &mut topic, &mut payload, |topic, payload| {
+            messages.push((topic.to_string(), payload.to_string()))
+        })
+        .unwrap();
+    assert_eq!(3, messages.len());
+    assert_eq!("homeassistant/sensor/garden/uva/config", messages[0].0);
+    assert_eq!("homeassistant/sensor/garden/uvi/config", messages[2].0);
+    assert!(messages[1].1.contains("\"unique_id\":\"garden_uvb\""));
+    assert!(messages[2]
+        .1
+        .ends_with("\"model\":\"VEML6075\",\"hw_version\":\"0x0026\"}}"));
+    assert!(device
+        .discovery(0x26, &mut topic, &mut payload[..100], |_, _| ())
+        .is_err());
+}
+
+#[test]
+fn can_display_error() {
+    let e: Error<&str> = Error::I2C("NACK");
+    assert_eq!("I2C bus error: \"NACK\"", format!("{}", e));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn error_is_std_error() {
+    fn check<T: std::error::Error>(_: &T) {}
+    check(&Error::I2C(()));
+}
+
+#[test]
+fn can_display_error_variants() {
+    assert_eq!("Invalid device", format!("{}", Error::<()>::InvalidDevice));
+    assert_eq!(
+        "Invalid calibration",
+        format!("{}", Error::<()>::InvalidCalibration)
+    );
+    assert_eq!(
+        "Unknown calibration",
+        format!("{}", Error::<()>::UnknownCalibration)
+    );
+    assert_eq!(
+        "Repeated register reads mismatch",
+        format!("{}", Error::<()>::ReadMismatch)
+    );
+    assert_eq!(
+        "Configuration mismatch: expected 0x30, read 0x01",
+        format!(
+            "{}",
+            Error::<()>::ConfigMismatch {
+                expected: 0x30,
+                actual: 1
+            }
+        )
+    );
+}
+
+static RETRIES: AtomicU8 = AtomicU8::new(0);
+
+fn count_retry(retry: u8) {
+    RETRIES.store(retry, Ordering::SeqCst);
+}
+
+#[test]
+fn retries_failed_transactions() {
+    let transactions = [
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVA], vec![0xCD, 0xAB])
+            .with_error(MockError::Io(ErrorKind::Other)),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVA], vec![0xCD, 0xAB]),
+        I2cTrans::write(DEVICE_ADDRESS, vec![Register::CONFIG, 0, 0])
+            .with_error(MockError::Io(ErrorKind::Other)),
+        I2cTrans::write(DEVICE_ADDRESS, vec![Register::CONFIG, 0, 0])
+            .with_error(MockError::Io(ErrorKind::Other)),
+    ];
+    let mut dev = new(&transactions);
+    dev.set_retry_policy(RetryPolicy {
+        retries: 1,
+        on_retry: Some(count_retry),
+    });
+    assert_eq!(0xABCD, dev.read_uva_raw().unwrap());
+    assert_eq!(1, RETRIES.load(Ordering::SeqCst));
+    match dev.enable() {
+        Err(Error::I2C(_)) => (),
+        _ => panic!("Error expected"),
+    }
+    destroy(dev);
+}
+
+#[test]
+fn counts_bus_errors() {
+    let transactions = [
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVA], vec![0xCD, 0xAB])
+            .with_error(MockError::Io(ErrorKind::Other)),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVA], vec![0xCD, 0xAB]),
+        I2cTrans::write(DEVICE_ADDRESS, vec![Register::CONFIG, 0, 0])
+            .with_error(MockError::Io(ErrorKind::Other)),
+        I2cTrans::write(DEVICE_ADDRESS, vec![Register::CONFIG, 0, 0])
+            .with_error(MockError::Io(ErrorKind::Other)),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::DEVICE_ID], vec![0x26, 0]),
+        I2cTrans::write(DEVICE_ADDRESS, vec![Register::CONFIG, 0b0000_0001, 0]),
+    ];
+    let mut dev = new(&transactions);
+    dev.set_retry_policy(RetryPolicy {
+        retries: 1,
+        on_retry: None,
+    });
+    dev.read_uva_raw().unwrap();
+    assert!(dev.enable().is_err());
+    dev.recover().unwrap();
+    assert_eq!(
+        BusStats {
+            errors: 3,
+            retries: 2,
+            recoveries: 1
+        },
+        dev.stats()
+    );
+    dev.reset_stats();
+    assert_eq!(BusStats::default(), dev.stats());
+    destroy(dev);
+}
+
+#[test]
+fn can_recover() {
+    let transactions = [
+        I2cTrans::write(DEVICE_ADDRESS, vec![Register::CONFIG, 0b0011_0001, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::DEVICE_ID], vec![0x26, 0]),
+        I2cTrans::write(DEVICE_ADDRESS, vec![Register::CONFIG, 0b0011_0001, 0]),
+    ];
+    let mut dev = new(&transactions);
+    dev.set_integration_time(IT::Ms400).unwrap();
+    dev.recover().unwrap();
+    destroy(dev);
+}
+
+#[test]
+fn recover_checks_device_id() {
+    let transactions = [I2cTrans::write_read(
+        DEVICE_ADDRESS,
+        vec![Register::DEVICE_ID],
+        vec![0x27, 0],
+    )];
+    let mut dev = new(&transactions);
+    match dev.recover() {
+        Err(Error::InvalidDevice) => (),
+        _ => panic!("Error expected"),
+    }
+    destroy(dev);
+}
+
+static OBSERVED: Mutex<Vec<(Direction, u8, u16)>> = Mutex::new(Vec::new());
+
+fn observe(direction: Direction, register: u8, value: u16) {
+    OBSERVED.lock().unwrap().push((direction, register, value));
+}
+
+#[test]
+fn can_observe_bus() {
+    let transactions = [
+        I2cTrans::write(DEVICE_ADDRESS, vec![Register::CONFIG, 0, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVA], vec![0xCD, 0xAB]),
+    ];
+    let mut dev = new(&transactions);
+    dev.set_bus_observer(Some(observe));
+    dev.enable().unwrap();
+    dev.read_uva_raw().unwrap();
+    assert_eq!(
+        vec![
+            (Direction::Write, Register::CONFIG, 0),
+            (Direction::Read, Register::UVA, 0xABCD)
+        ],
+        *OBSERVED.lock().unwrap()
+    );
+    destroy(dev);
+}
+
+#[test]
+fn can_share_bus() {
+    let other_address = 0x20;
+    let transactions = [
+        I2cTrans::write(DEVICE_ADDRESS, vec![Register::CONFIG, 0, 0]),
+        I2cTrans::write(other_address, vec![0xAB]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVA], vec![0xCD, 0xAB]),
+    ];
+    let mut i2c = I2cMock::new(&transactions);
+    let bus = shared_bus::BusManagerSimple::new(i2c.clone());
+    let mut sensor = Veml6075::new(bus.acquire_i2c(), Calibration::default());
+    let mut other = bus.acquire_i2c();
+    sensor.enable().unwrap();
+    other.write(other_address, &[0xAB]).unwrap();
+    assert_eq!(0xABCD, sensor.read_uva_raw().unwrap());
+    sensor.destroy();
+    i2c.done();
+}
+
+#[test]
+fn can_use_mux_channel() {
+    let mux_address = 0x70;
+    let transactions = [
+        I2cTrans::write(mux_address, vec![0b0000_1000]),
+        I2cTrans::write(DEVICE_ADDRESS, vec![Register::CONFIG, 0, 0]),
+        I2cTrans::write(mux_address, vec![0b0000_1000]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVA], vec![0xCD, 0xAB]),
+    ];
+    let mut sensor = MuxedVeml6075::new_muxed(
+        I2cMock::new(&transactions),
+        mux_address,
+        3,
+        Calibration::default(),
+    )
+    .unwrap();
+    sensor.enable().unwrap();
+    assert_eq!(0xABCD, sensor.read_uva_raw().unwrap());
+    sensor.destroy().destroy().done();
+}
+
+#[test]
+fn rejects_invalid_mux_channel() {
+    let i2c = I2cMock::new(&[]);
+    assert!(MuxedVeml6075::new_muxed(i2c, 0x70, 8, Calibration::default()).is_none());
+}
+
+#[test]
+fn can_use_array() {
+    let transactions = [
+        I2cTrans::write(DEVICE_ADDRESS, vec![Register::CONFIG, 0b0001_0001, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVA], vec![0, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVB], vec![0, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVCOMP1], vec![0, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVCOMP2], vec![0, 0]),
+    ];
+    let mut array = Veml6075Array::new([new(&transactions), new(&transactions)]);
+    array.set_integration_time(IT::Ms100).unwrap();
+    let m = array.read_all().unwrap();
+    assert_eq!(2, m.len());
+    assert_eq!(m[0], m[1]);
+    let [a, b] = array.destroy();
+    destroy(a);
+    destroy(b);
+}
+
+fn read_generic<S: UvSensor>(sensor: &mut S) -> Result<Measurement, S::Error> {
+    sensor.enable()?;
+    sensor.read_uv()
+}
+
+#[test]
+fn can_use_uv_sensor_trait() {
+    let transactions = [
+        I2cTrans::write(DEVICE_ADDRESS, vec![Register::CONFIG, 0, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVA], vec![0, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVB], vec![0, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVCOMP1], vec![0, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVCOMP2], vec![0, 0]),
+    ];
+    let mut dev = new(&transactions);
+    let m = read_generic(&mut dev).unwrap();
+    assert_eq!(0.0, m.uv_index);
+    destroy(dev);
+}
+
+#[cfg(feature = "veml6070")]
+#[test]
+fn can_use_veml6070() {
+    use veml6075::{Veml6070, Veml6070IntegrationTime};
+    let transactions = [
+        I2cTrans::write(0x38, vec![0b0000_1011]),
+        I2cTrans::write(0x38, vec![0b0000_1010]),
+        I2cTrans::read(0x39, vec![0x01]),
+        I2cTrans::read(0x38, vec![0x76]),
+    ];
+    let mut dev = Veml6070::new(I2cMock::new(&transactions));
+    dev.set_integration_time(Veml6070IntegrationTime::T2)
+        .unwrap();
+    let m = read_generic(&mut dev).unwrap_or_else(|_| panic!());
+    assert_eq!(187.0, m.uva);
+    assert!((m.uv_index - 1.0).abs() < 0.01);
+    dev.destroy().done();
+}
+
+#[cfg(feature = "sim")]
+mod sim {
+    use super::*;
+    use embedded_hal::blocking::delay::DelayMs;
+    use veml6075::hil;
+    use veml6075::sim::{FakeVeml6075, SimError, SyntheticSun};
+
+    struct SimDelay<'a>(&'a FakeVeml6075);
+
+    impl DelayMs<u16> for SimDelay<'_> {
+        fn delay_ms(&mut self, ms: u16) {
+            self.0.advance(u32::from(ms));
+        }
+    }
+
+    const INPUT: RawMeasurement = RawMeasurement {
+        uva: 1000,
+        uvb: 2000,
+        uvcomp1: 300,
+        uvcomp2: 400,
+    };
+
+    #[test]
+    fn continuous_mode_updates_after_integration_time() {
+        let fake = FakeVeml6075::new();
+        fake.set_input(INPUT);
+        let mut dev = Veml6075::new(&fake, Calibration::default());
+        dev.set_integration_time(IT::Ms200).unwrap();
+        dev.enable().unwrap();
+        fake.advance(199);
+        assert_eq!(0, dev.read_uva_raw().unwrap());
+        fake.advance(1);
+        assert_eq!(2000, dev.read_uva_raw().unwrap());
+        assert_eq!(0x26, dev.read_device_id().unwrap());
+    }
+
+    #[test]
+    fn active_force_mode_needs_trigger() {
+        let fake = FakeVeml6075::new();
+        fake.set_input(INPUT);
+        let mut dev = Veml6075::new(&fake, Calibration::default());
+        dev.set_mode(Mode::ActiveForce).unwrap();
+        dev.enable().unwrap();
+        fake.advance(100);
+        assert_eq!(0, dev.read_uvb_raw().unwrap());
+        dev.trigger_measurement().unwrap();
+        assert!(fake.is_converting());
+        fake.advance(50);
+        assert!(!fake.is_converting());
+        assert_eq!(1000, dev.read_uvb_raw().unwrap());
+    }
+
+    #[test]
+    fn high_dynamic_and_saturation() {
+        let fake = FakeVeml6075::new();
+        fake.set_input(RawMeasurement {
+            uva: 20000,
+            ..INPUT
+        });
+        let mut dev = Veml6075::new(&fake, Calibration::default());
+        dev.set_integration_time(IT::Ms800).unwrap();
+        dev.set_dynamic_setting(DS::High).unwrap();
+        dev.enable().unwrap();
+        fake.advance(800);
+        let raw = dev.read_raw().unwrap();
+        assert_eq!(0xFFFF, raw.uva);
+        assert_eq!(8000, raw.uvb);
+    }
+
+    #[test]
+    fn can_inject_faults() {
+        let fake = FakeVeml6075::new();
+        fake.set_input(INPUT);
+        let mut dev = Veml6075::new(&fake, Calibration::default());
+        dev.enable().unwrap();
+        fake.advance(50);
+        fake.inject_nacks(1);
+        match dev.read_uva_raw() {
+            Err(Error::I2C(SimError::Nack)) => (),
+            _ => panic!("Error expected"),
+        }
+        assert_eq!(500, dev.read_uva_raw().unwrap());
+        fake.inject_corrupted_reads(1, 0xFF00);
+        assert_eq!(500 ^ 0xFF00, dev.read_uva_raw().unwrap());
+        assert_eq!(500, dev.read_uva_raw().unwrap());
+        fake.set_stuck_at(Some(0x1234));
+        assert_eq!(0x1234, dev.read_uvb_raw().unwrap());
+        assert_eq!(0x26, dev.read_device_id().unwrap());
+        fake.set_stuck_at(None);
+        assert_eq!(1000, dev.read_uvb_raw().unwrap());
+    }
+
+    #[test]
+    fn can_run_hil_test() {
+        let fake = FakeVeml6075::new();
+        fake.set_input(INPUT);
+        let mut dev = Veml6075::new(&fake, Calibration::default());
+        dev.set_integration_time(IT::Ms200).unwrap();
+        let limits = hil::Limits {
+            min: RawMeasurement {
+                uva: 900,
+                uvb: 1900,
+                uvcomp1: 250,
+                uvcomp2: 350,
+            },
+            max: RawMeasurement {
+                uva: 1100,
+                uvb: 2100,
+                uvcomp1: 350,
+                uvcomp2: 450,
+            },
+        };
+        let mut delay = SimDelay(&fake);
+        let report = hil::run(&mut dev, &mut delay, &limits).unwrap();
+        assert!(report.passed());
+        let steps = report.steps.unwrap();
+        assert_eq!(IT::Ms800, steps[9].integration_time);
+        assert_eq!(DS::High, steps[9].dynamic_setting);
+        assert_eq!(4000, steps[9].raw.uva);
+        assert_eq!(0b0010_0001, fake.config());
+
+        fake.set_stuck_at(Some(0));
+        let report = hil::run(&mut dev, &mut delay, &limits).unwrap();
+        assert!(!report.passed());
+
+        fake.set_stuck_at(None);
+        fake.set_input(RawMeasurement {
+            uva: 0xFFFF,
+            uvb: 0xFFFF,
+            uvcomp1: 0xFFFF,
+            uvcomp2: 0xFFFF,
+        });
+        let report = hil::run(&mut dev, &mut delay, &limits).unwrap();
+        assert!(!report.passed());
+
+        fake.set_stuck_at(Some(0xFFFF));
+        let report = hil::run(&mut dev, &mut delay, &limits).unwrap();
+        let steps = report.steps.unwrap();
+        assert!(steps.iter().all(|step| step.saturated));
+        assert!(!steps[1].passed);
+        assert!(!report.passed());
+        fake.set_stuck_at(None);
+    }
+
+    #[test]
+    fn synthetic_sun_is_deterministic() {
+        const HOUR: u32 = 3600 * 1000;
+        let sun = SyntheticSun::default();
+        let zero = RawMeasurement {
+            uva: 0,
+            uvb: 0,
+            uvcomp1: 0,
+            uvcomp2: 0,
+        };
+        assert_eq!(zero, sun.input_at(5 * HOUR));
+        assert_eq!(zero, sun.input_at(21 * HOUR));
+        assert_eq!(sun.noon, sun.input_at(13 * HOUR));
+        assert_eq!(sun.noon, sun.input_at(37 * HOUR));
+        assert!(sun.input_at(10 * HOUR).uva < sun.noon.uva);
+        let cloudy = SyntheticSun {
+            clouds: Some(42),
+            ..sun
+        };
+        for hour in 7..20 {
+            let input = cloudy.input_at(hour * HOUR);
+            assert_eq!(input, cloudy.input_at(hour * HOUR));
+            assert!(input.uva <= sun.input_at(hour * HOUR).uva);
+            assert!(input.uva >= sun.input_at(hour * HOUR).uva * 3 / 10);
+        }
+    }
+}
+
+#[cfg(feature = "test-util")]
+#[test]
+fn can_use_test_util() {
+    use veml6075::test_util;
+    let raw = RawMeasurement {
+        uva: 1,
+        uvb: 2,
+        uvcomp1: 3,
+        uvcomp2: 0x0405,
+    };
+    let mut transactions = vec![test_util::config_write(0)];
+    transactions.extend_from_slice(&test_util::raw_measurement_read(&raw));
+    let mut dev = test_util::new(&transactions);
+    dev.enable().unwrap();
+    assert_eq!(raw, dev.read_raw().unwrap());
+    test_util::destroy(dev);
+}
+
+#[test]
+fn can_read_oneshot_in_active_force_mode() {
+    let transactions = [
+        I2cTrans::write(DEVICE_ADDRESS, vec![Register::CONFIG, 0b0000_0011, 0]),
+        I2cTrans::write(DEVICE_ADDRESS, vec![Register::CONFIG, 0b0000_0010, 0]),
+        I2cTrans::write(DEVICE_ADDRESS, vec![Register::CONFIG, 0b0000_0110, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVA], vec![0x10, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVB], vec![0x20, 0]),
+    ];
+    let mut dev = new(&transactions);
+    dev.set_calibration(Calibration::new(0.0, 0.0, 0.0, 0.0, 1.0, 1.0))
+        .unwrap();
+    dev.set_mode(Mode::ActiveForce).unwrap();
+    dev.enable().unwrap();
+    let m = dev.read_oneshot(&mut MockNoop::new()).unwrap();
+    assert_eq!(16.0, m.uva);
+    assert_eq!(32.0, m.uvb);
+    destroy(dev);
+}
+
+#[test]
+fn read_oneshot_does_not_trigger_in_continuous_mode() {
+    let transactions = [
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVA], vec![0x10, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVB], vec![0x20, 0]),
+    ];
+    let mut dev = new(&transactions);
+    dev.set_calibration(Calibration::new(0.0, 0.0, 0.0, 0.0, 1.0, 1.0))
+        .unwrap();
+    let m = dev.read_oneshot(&mut MockNoop::new()).unwrap();
+    assert_eq!(16.0, m.uva);
+    destroy(dev);
+}
+
+struct RecordingDelay(u32);
+
+impl embedded_hal::blocking::delay::DelayMs<u16> for RecordingDelay {
+    fn delay_ms(&mut self, ms: u16) {
+        self.0 += u32::from(ms);
+    }
+}
+
+#[test]
+fn read_blocking_waits_only_after_config_change() {
+    let mut transactions = vec![I2cTrans::write(
+        DEVICE_ADDRESS,
+        vec![Register::CONFIG, 0, 0],
+    )];
+    for _ in 0..2 {
+        transactions.push(I2cTrans::write_read(
+            DEVICE_ADDRESS,
+            vec![Register::UVA],
+            vec![0, 0],
+        ));
+        transactions.push(I2cTrans::write_read(
+            DEVICE_ADDRESS,
+            vec![Register::UVB],
+            vec![0, 0],
+        ));
+    }
+    let mut dev = new(&transactions);
+    dev.set_calibration(Calibration::new(0.0, 0.0, 0.0, 0.0, 1.0, 1.0))
+        .unwrap();
+    dev.enable().unwrap();
+    let mut delay = RecordingDelay(0);
+    dev.read_blocking(&mut delay).unwrap();
+    assert_eq!(50, delay.0);
+    dev.read_blocking(&mut delay).unwrap();
+    assert_eq!(50, delay.0);
+    destroy(dev);
+}
+
+#[test]
+fn can_get_min_sample_interval() {
+    let transactions = [I2cTrans::write(
+        DEVICE_ADDRESS,
+        vec![Register::CONFIG, 0b0001_0001, 0],
+    )];
+    let mut dev = new(&transactions);
+    dev.set_integration_time(IT::Ms100).unwrap();
+    assert_eq!(
+        core::time::Duration::from_millis(110),
+        dev.min_sample_interval()
+    );
+    assert!((dev.max_sample_rate_hz() - 9.0909).abs() < 0.001);
+    destroy(dev);
+}
+
+#[test]
+fn can_iterate_measurements() {
+    let mut transactions = vec![
+        I2cTrans::write(DEVICE_ADDRESS, vec![Register::CONFIG, 0b0001_0001, 0]),
+        I2cTrans::write(DEVICE_ADDRESS, vec![Register::CONFIG, 0b0001_0011, 0]),
+        I2cTrans::write(DEVICE_ADDRESS, vec![Register::CONFIG, 0b0001_0010, 0]),
+    ];
+    for _ in 0..2 {
+        transactions.push(I2cTrans::write(
+            DEVICE_ADDRESS,
+            vec![Register::CONFIG, 0b0001_0110, 0],
+        ));
+        transactions.push(I2cTrans::write_read(
+            DEVICE_ADDRESS,
+            vec![Register::UVA],
+            vec![0, 0],
+        ));
+        transactions.push(I2cTrans::write_read(
+            DEVICE_ADDRESS,
+            vec![Register::UVB],
+            vec![0, 0],
+        ));
+        transactions.push(I2cTrans::write_read(
+            DEVICE_ADDRESS,
+            vec![Register::UVCOMP1],
+            vec![0, 0],
+        ));
+        transactions.push(I2cTrans::write_read(
+            DEVICE_ADDRESS,
+            vec![Register::UVCOMP2],
+            vec![0, 0],
+        ));
+    }
+    let mut dev = new(&transactions);
+    dev.set_integration_time(IT::Ms100).unwrap();
+    dev.set_mode(Mode::ActiveForce).unwrap();
+    dev.enable().unwrap();
+    let mut delay = MockNoop::new();
+    let count = dev
+        .iter_measurements(&mut delay)
+        .take(2)
+        .filter(|m| m.is_ok())
+        .count();
+    assert_eq!(2, count);
+    destroy(dev);
+}
+
+static MEASUREMENTS: AtomicU8 = AtomicU8::new(0);
+
+fn count_measurement(_: &Measurement) {
+    MEASUREMENTS.fetch_add(1, Ordering::SeqCst);
+}
+
+#[test]
+fn can_sample_continuous() {
+    let transactions = [
+        I2cTrans::write(DEVICE_ADDRESS, vec![Register::CONFIG, 0, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVA], vec![0, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVB], vec![0, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVCOMP1], vec![0, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVCOMP2], vec![0, 0]),
+    ];
+    let mut sampler = Sampler::new(new(&transactions), MockNoop::new());
+    sampler.sensor_mut().enable().unwrap();
+    sampler.set_on_measurement(Some(count_measurement));
+    sampler.next_sample().unwrap();
+    assert_eq!(1, MEASUREMENTS.load(Ordering::SeqCst));
+    let (dev, _delay) = sampler.destroy();
+    destroy(dev);
+}
+
+#[test]
+fn thresholds_report_crossings() {
+    let mut t = Thresholds::new(&[3.0, 6.0, 8.0]);
+    let mut events = Vec::new();
+    t.update(7.0, |e| events.push(e));
+    t.update(7.5, |e| events.push(e));
+    t.update(2.0, |e| events.push(e));
+    assert_eq!(
+        vec![
+            Event::CrossedUp(3.0),
+            Event::CrossedUp(6.0),
+            Event::CrossedDown(6.0),
+            Event::CrossedDown(3.0)
+        ],
+        events
+    );
+}
+
+#[test]
+fn sampler_queues_threshold_events() {
+    let mut transactions = vec![I2cTrans::write(
+        DEVICE_ADDRESS,
+        vec![Register::CONFIG, 0, 0],
+    )];
+    for uva in [4, 8, 2].iter() {
+        transactions.push(I2cTrans::write_read(
+            DEVICE_ADDRESS,
+            vec![Register::UVA],
+            vec![*uva, 0],
+        ));
+        transactions.push(I2cTrans::write_read(
+            DEVICE_ADDRESS,
+            vec![Register::UVB],
+            vec![0, 0],
+        ));
+    }
+    let mut dev = new(&transactions);
+    dev.set_calibration(Calibration::new(0.0, 0.0, 0.0, 0.0, 1.0, 1.0))
+        .unwrap();
+    let mut sampler = Sampler::new(dev, MockNoop::new());
+    sampler.sensor_mut().enable().unwrap();
+    sampler.set_thresholds(Some(Thresholds::new(&[3.0])));
+    sampler.next_sample().unwrap(); // UVI 2
+    assert_eq!(None, sampler.next_event());
+    sampler.next_sample().unwrap(); // UVI 4
+    sampler.next_sample().unwrap(); // UVI 1
+    assert_eq!(Some(Event::CrossedUp(3.0)), sampler.next_event());
+    assert_eq!(Some(Event::CrossedDown(3.0)), sampler.next_event());
+    assert_eq!(None, sampler.next_event());
+    let (dev, _delay) = sampler.destroy();
+    destroy(dev);
+}
+
+#[test]
+fn sunburn_guard_warns() {
+    use veml6075::dose::{SkinType, SunburnGuard};
+    // UVI 10 is 0.25 W/m²: 200 J/m² (MED of skin type I) after 800 s.
+    let mut guard = SunburnGuard::new(SkinType::I, 2.0);
+    let mut events = Vec::new();
+    guard.update(10.0, 800_000, |e| events.push(e));
+    assert_eq!(vec![Event::CrossedUp(50.0)], events);
+    assert!((guard.dose() - 100.0).abs() < 1e-3);
+    guard.update(10.0, 800_000, |e| events.push(e));
+    assert_eq!(
+        vec![
+            Event::CrossedUp(50.0),
+            Event::CrossedUp(80.0),
+            Event::CrossedUp(100.0)
+        ],
+        events
+    );
+    guard.reset();
+    assert_eq!(0.0, guard.med_percent());
+    events.clear();
+    guard.update(10.0, 400_000, |e| events.push(e));
+    assert!(events.is_empty());
+}
+
+#[test]
+fn daily_dose_closes_days() {
+    use veml6075::dose::{DailyDose, MS_PER_DAY};
+    let mut daily = DailyDose::new();
+    assert_eq!(None, daily.yesterday());
+    daily.update_at(MS_PER_DAY - 1, 4.0, 1000);
+    daily.update_at(MS_PER_DAY - 1, 4.0, 1000);
+    assert_eq!(0.2, daily.today());
+    daily.update_at(MS_PER_DAY, 2.0, 1000);
+    assert_eq!(Some(0.2), daily.yesterday());
+    assert_eq!(0.05, daily.today());
+    daily.update_at(3 * MS_PER_DAY, 2.0, 1000);
+    assert_eq!(Some(0.0), daily.yesterday());
+    assert_eq!(0.05, daily.today());
+}
+
+#[test]
+fn can_detect_trend() {
+    use veml6075::trend::{Trend, TrendAnalyzer};
+    let mut trend = TrendAnalyzer::new(3, 1.0);
+    assert_eq!(None, trend.update(0, 5.0));
+    assert_eq!(None, trend.update(0, 5.0));
+    assert_eq!(Some(Trend::Steady), trend.update(3_600_000, 5.5));
+    assert_eq!(Some(Trend::Rising), trend.update(7_200_000, 7.5));
+    assert_eq!(Some(Trend::Falling), trend.update(10_800_000, 2.0));
+    assert_eq!(Some(Trend::Falling), trend.update(14_400_000, 1.0));
+    trend.reset();
+    assert_eq!(None, trend.slope());
+}
+
+#[test]
+fn can_schedule_active_force() {
+    let mut transactions = vec![I2cTrans::write(
+        DEVICE_ADDRESS,
+        vec![Register::CONFIG, 0b0000_0011, 0],
+    )];
+    for _ in 0..2 {
+        transactions.push(I2cTrans::write(
+            DEVICE_ADDRESS,
+            vec![Register::CONFIG, 0b0000_0111, 0],
+        ));
+        transactions.push(I2cTrans::write_read(
+            DEVICE_ADDRESS,
+            vec![Register::UVA],
+            vec![0, 0],
+        ));
+        transactions.push(I2cTrans::write_read(
+            DEVICE_ADDRESS,
+            vec![Register::UVB],
+            vec![0, 0],
+        ));
+        transactions.push(I2cTrans::write_read(
+            DEVICE_ADDRESS,
+            vec![Register::UVCOMP1],
+            vec![0, 0],
+        ));
+        transactions.push(I2cTrans::write_read(
+            DEVICE_ADDRESS,
+            vec![Register::UVCOMP2],
+            vec![0, 0],
+        ));
+    }
+    let mut dev = new(&transactions);
+    dev.set_mode(Mode::ActiveForce).unwrap();
+    let mut scheduler = Scheduler::new(1000);
+    assert_eq!(None, scheduler.tick(&mut dev, 10).unwrap()); // trigger
+    assert_eq!(None, scheduler.tick(&mut dev, 40).unwrap());
+    assert!(scheduler.tick(&mut dev, 10).unwrap().is_some()); // 50 ms: read
+    assert_eq!(None, scheduler.tick(&mut dev, 500).unwrap());
+    assert_eq!(None, scheduler.tick(&mut dev, 500).unwrap()); // trigger
+    assert!(scheduler.tick(&mut dev, 50).unwrap().is_some());
+    destroy(dev);
+}
+
+#[test]
+fn pump_pushes_to_all_sinks() {
+    let transactions = [
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVA], vec![0, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVB], vec![0, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVCOMP1], vec![0, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVCOMP2], vec![0, 0]),
+    ];
+    let mut dev = new(&transactions);
+    let mut pump = Pump::new(1000);
+    let mut first = Vec::new();
+    let mut count = 0;
+    {
+        let mut log = |m: &TimestampedMeasurement| first.push(*m);
+        let mut counter = |_: &TimestampedMeasurement| count += 1;
+        let clock = FixedClock(42);
+        let m = pump
+            .tick(&mut dev, &clock, 10, &mut [&mut log, &mut counter])
+            .unwrap();
+        assert_eq!(42, m.unwrap().at);
+        assert_eq!(
+            None,
+            pump.tick(&mut dev, &clock, 10, &mut [&mut log]).unwrap()
+        );
+    }
+    assert_eq!(1, first.len());
+    assert_eq!(1, count);
+    destroy(dev);
+}
+
+#[test]
+fn can_poll() {
+    let transactions = [
+        I2cTrans::write(DEVICE_ADDRESS, vec![Register::CONFIG, 0, 0]),
+        I2cTrans::write(DEVICE_ADDRESS, vec![Register::CONFIG, 0b0000_0010, 0]),
+        I2cTrans::write(DEVICE_ADDRESS, vec![Register::CONFIG, 0b0000_0110, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVA], vec![0, 0])
+            .with_error(MockError::Io(ErrorKind::Other)),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVA], vec![0, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVB], vec![0, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVCOMP1], vec![0, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVCOMP2], vec![0, 0]),
+        I2cTrans::write(DEVICE_ADDRESS, vec![Register::CONFIG, 0b0000_0110, 0]),
+    ];
+    let mut dev = new(&transactions);
+    dev.enable().unwrap();
+    dev.set_mode(Mode::ActiveForce).unwrap();
+    let mut poller = Poller::new(20);
+    assert_eq!(PollState::Idle, poller.state());
+    assert_eq!(None, poller.poll(&mut dev).unwrap()); // trigger
+    assert_eq!(None, poller.poll(&mut dev).unwrap());
+    assert_eq!(PollState::Triggered(20), poller.state());
+    assert_eq!(None, poller.poll(&mut dev).unwrap());
+    assert!(poller.poll(&mut dev).is_err()); // 60 ms: read fails
+    assert_eq!(PollState::Ready, poller.state());
+    assert!(poller.poll(&mut dev).unwrap().is_some());
+    assert_eq!(PollState::Idle, poller.state());
+    assert_eq!(None, poller.poll(&mut dev).unwrap()); // trigger
+    poller.reset();
+    assert_eq!(PollState::Idle, poller.state());
+    destroy(dev);
+}
+
+#[test]
+fn can_sample_low_power() {
+    let transactions = [
+        I2cTrans::write(DEVICE_ADDRESS, vec![Register::CONFIG, 0b0000_0001, 0]),
+        I2cTrans::write(DEVICE_ADDRESS, vec![Register::CONFIG, 0b0000_0011, 0]),
+        I2cTrans::write(DEVICE_ADDRESS, vec![Register::CONFIG, 0b0000_0010, 0]),
+        I2cTrans::write(DEVICE_ADDRESS, vec![Register::CONFIG, 0b0000_0110, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVA], vec![0, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVB], vec![0, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVCOMP1], vec![0, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVCOMP2], vec![0, 0]),
+        I2cTrans::write(DEVICE_ADDRESS, vec![Register::CONFIG, 0b0000_0011, 0]),
+    ];
+    let mut sampler = LowPowerSampler::new(new(&transactions), MockNoop::new()).unwrap();
+    sampler.next_sample().unwrap();
+    let (dev, _delay) = sampler.destroy();
+    destroy(dev);
+}
+
+#[test]
+fn can_estimate_power() {
+    use veml6075::power::{estimate, MicroAmps};
+    assert_eq!(
+        MicroAmps(480.0),
+        estimate(IT::Ms100, Mode::Continuous, 10_000)
+    );
+    assert_eq!(MicroAmps(480.0), estimate(IT::Ms100, Mode::ActiveForce, 50));
+    let MicroAmps(current) = estimate(IT::Ms100, Mode::ActiveForce, 1000);
+    assert!((current - (48.0 + 0.72)).abs() < 0.01);
+}
+
+#[test]
+fn enabled_scope_disables_on_drop() {
+    let transactions = [
+        I2cTrans::write(DEVICE_ADDRESS, vec![Register::CONFIG, 0, 0]),
+        I2cTrans::write(DEVICE_ADDRESS, vec![Register::CONFIG, 0b0000_0100, 0]),
+        I2cTrans::write(DEVICE_ADDRESS, vec![Register::CONFIG, 1, 0]),
+    ];
+    let mut dev = new(&transactions);
+    {
+        let mut scope = dev.enabled_scope().unwrap();
+        scope.trigger_measurement().unwrap();
+    }
+    destroy(dev);
+}
+
+#[test]
+fn shutdown_on_destroy() {
+    let transactions = [
+        I2cTrans::write(DEVICE_ADDRESS, vec![Register::CONFIG, 0, 0]),
+        I2cTrans::write(DEVICE_ADDRESS, vec![Register::CONFIG, 1, 0]),
+    ];
+    let mut dev = ShutdownOnDrop::new(new(&transactions));
+    dev.enable().unwrap();
+    dev.destroy().done();
+}
+
+#[test]
+fn shutdown_on_drop() {
+    let transactions = [
+        I2cTrans::write(DEVICE_ADDRESS, vec![Register::CONFIG, 0, 0]),
+        I2cTrans::write(DEVICE_ADDRESS, vec![Register::CONFIG, 1, 0]),
+    ];
+    let mut i2c = I2cMock::new(&transactions);
+    let mut dev = ShutdownOnDrop::new(Veml6075::new(i2c.clone(), Calibration::default()));
+    dev.enable().unwrap();
+    drop(dev);
+    i2c.done();
+}
+
+struct FixedClock(u64);
+
+impl Clock for FixedClock {
+    fn now_ms(&self) -> u64 {
+        self.0
+    }
+}
+
+#[test]
+fn can_read_timestamped() {
+    let transactions = [
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVA], vec![0, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVB], vec![0, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVCOMP1], vec![0, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVCOMP2], vec![0, 0]),
+    ];
+    let mut dev = new(&transactions);
+    let m = dev.read_timestamped(&FixedClock(1234)).unwrap();
+    assert_eq!(1234, m.at);
+    assert_eq!(0.0, m.measurement.uv_index);
+    destroy(dev);
+}
+
+#[test]
+fn can_read_cached() {
+    let transactions = [
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVA], vec![0, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVB], vec![0, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVCOMP1], vec![0, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVCOMP2], vec![0, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVA], vec![100, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVB], vec![0, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVCOMP1], vec![0, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVCOMP2], vec![0, 0]),
+    ];
+    let mut dev = new(&transactions);
+    assert_eq!(1000, dev.read_cached(&FixedClock(1000), 500).unwrap().at);
+    let m = dev.read_cached(&FixedClock(1500), 500).unwrap();
+    assert_eq!(1000, m.at);
+    assert_eq!(0.0, m.measurement.uva);
+    let m = dev.read_cached(&FixedClock(1501), 500).unwrap();
+    assert_eq!(1501, m.at);
+    assert_eq!(100.0, m.measurement.uva);
+    destroy(dev);
+}
+
+#[test]
+fn config_change_discards_cached() {
+    let transactions = [
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVA], vec![0, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVB], vec![0, 0]),
+        I2cTrans::write(DEVICE_ADDRESS, vec![Register::CONFIG, 0b0001_0001, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVA], vec![100, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVB], vec![0, 0]),
+    ];
+    let mut dev = new(&transactions);
+    dev.set_calibration(Calibration::new(0.0, 0.0, 0.0, 0.0, 1.0, 1.0))
+        .unwrap();
+    assert_eq!(
+        0.0,
+        dev.read_cached(&FixedClock(1000), 500)
+            .unwrap()
+            .measurement
+            .uva
+    );
+    dev.set_integration_time(IT::Ms100).unwrap();
+    let m = dev.read_cached(&FixedClock(1100), 500).unwrap();
+    assert_eq!(1100, m.at);
+    assert_eq!(100.0, m.measurement.uva);
+    destroy(dev);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn can_read_system_time() {
+    use std::time::{Duration, SystemTime};
+    use veml6075::SystemClock;
+    let transactions = [
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVA], vec![0, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVB], vec![0, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVCOMP1], vec![0, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVCOMP2], vec![0, 0]),
+    ];
+    let mut dev = new(&transactions);
+    let m = dev.read_timestamped(&SystemClock).unwrap();
+    let elapsed = SystemTime::now().duration_since(m.system_time()).unwrap();
+    assert!(elapsed < Duration::from_secs(5));
+    destroy(dev);
+}
+
+#[test]
+fn kalman_filter_smooths() {
+    use veml6075::filter::Kalman;
+    let mut filter = Kalman::new(0.01, 1.0);
+    assert_eq!(None, filter.estimate());
+    let first = filter.update(5.0);
+    assert_eq!(5.0, first.value);
+    assert_eq!(1.0, first.variance);
+    let second = filter.update(7.0);
+    assert!(second.value > 5.0 && second.value < 7.0);
+    assert!(second.variance < first.variance);
+    filter.reset();
+    assert_eq!(None, filter.estimate());
+}
+
+#[test]
+fn angular_correction_is_applied() {
+    let transactions = [
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVA], vec![100, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVB], vec![200, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVCOMP1], vec![0, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVCOMP2], vec![0, 0]),
+    ];
+    let mut dev = new(&transactions);
+    dev.set_calibration(Calibration::for_cover(CoverType::None).with_angular_correction(1.5))
+        .unwrap();
+    assert_eq!(1.5, dev.calibration().angular_correction);
+    let m = dev.read().unwrap();
+    assert_eq!(150.0, m.uva);
+    assert_eq!(300.0, m.uvb);
+    destroy(dev);
+}
+
+#[test]
+fn transmission_is_compensated() {
+    let c = Calibration::for_cover(CoverType::None).with_transmission(0.5, 0.25);
+    let m = c.compensate(&RawMeasurement {
+        uva: 100,
+        uvb: 100,
+        uvcomp1: 0,
+        uvcomp2: 0,
+    });
+    assert_eq!(200.0, m.uva);
+    assert_eq!(400.0, m.uvb);
+}
+
+#[test]
+fn gain_trim_is_applied() {
+    let c = Calibration::new(1.0, 0.0, 1.0, 0.0, 1.0, 1.0).with_gain_trim(1.1, 0.5);
+    let m = c.compensate(&RawMeasurement {
+        uva: 300,
+        uvb: 300,
+        uvcomp1: 100,
+        uvcomp2: 0,
+    });
+    assert!((m.uva - 220.0).abs() < 1e-3);
+    assert_eq!(100.0, m.uvb);
+}
+
+#[test]
+fn calibration_blob_roundtrip() {
+    let c = Calibration::for_cover(CoverType::Pmma).with_gain_trim(1.05, 0.95);
+    let mut data = c.to_bytes();
+    assert_eq!(CALIBRATION_BLOB_LEN, data.len());
+    assert_eq!(CALIBRATION_BLOB_VERSION, data[0]);
+    assert_eq!(Ok(c), Calibration::from_bytes(&data));
+    assert_eq!(Err(BlobError::Length), Calibration::from_bytes(&data[..10]));
+    data[5] ^= 0x10;
+    assert_eq!(Err(BlobError::Checksum), Calibration::from_bytes(&data));
+    data[0] = 0xFF;
+    assert_eq!(
+        Err(BlobError::Version(0xFF)),
+        Calibration::from_bytes(&data)
+    );
+}
+
+#[test]
+fn calibration_blob_migrates_v1() {
+    let c = Calibration::for_cover(CoverType::Ptfe);
+    let current = c.to_bytes();
+    let mut v1 = [0; 46];
+    v1[0] = 1;
+    v1[1..45].copy_from_slice(&current[1..45]);
+    let sum = v1[..45].iter().fold(0_u8, |s, b| s.wrapping_add(*b));
+    v1[45] = sum.wrapping_neg();
+    assert_eq!(Ok(c), Calibration::from_bytes(&v1));
+    assert_eq!(Ok(Some(current)), migrate_calibration_blob(&v1));
+    assert_eq!(Ok(None), migrate_calibration_blob(&current));
+    v1[45] ^= 1;
+    assert_eq!(Err(BlobError::Checksum), migrate_calibration_blob(&v1));
+}
+
+static COVERS: [(&str, Calibration); 2] = [
+    ("open", Calibration::for_cover(CoverType::None)),
+    ("dome", Calibration::for_cover(CoverType::Ptfe)),
+];
+
+#[test]
+fn can_set_calibration_by_name() {
+    let mut dev = new(&[]);
+    dev.set_calibration_by_name("soda-lime-glass").unwrap();
+    assert_eq!(
+        Calibration::for_cover(CoverType::SodaLimeGlass),
+        dev.calibration()
+    );
+    dev.set_calibration_registry(&COVERS);
+    assert_eq!(2, dev.calibration_registry().len());
+    dev.set_calibration_by_name("dome").unwrap();
+    assert_eq!(Calibration::for_cover(CoverType::Ptfe), dev.calibration());
+    match dev.set_calibration_by_name("pmma") {
+        Err(Error::UnknownCalibration) => (),
+        _ => panic!("Unknown calibration expected"),
+    }
+    destroy(dev);
+}
+
+#[test]
+fn rejects_invalid_calibration() {
+    let invalid = [
+        Calibration::default().with_transmission(0.0, 1.0),
+        Calibration::default().with_transmission(1.0, -0.5),
+        Calibration::default().with_angular_correction(f32::NAN),
+    ];
+    let mut dev = new(&[]);
+    for c in invalid.iter() {
+        assert!(!c.is_valid());
+        match dev.set_calibration(*c) {
+            Err(Error::InvalidCalibration) => (),
+            _ => panic!("Invalid calibration expected"),
+        }
+    }
+    assert!(dev.calibration().is_valid());
+    destroy(dev);
+    match Veml6075::builder()
+        .calibration(invalid[0])
+        .build(I2cMock::new(&[]))
+    {
+        Err(Error::InvalidCalibration) => (),
+        _ => panic!("Invalid calibration expected"),
+    }
+    let token = SuspendToken {
+        address: DEVICE_ADDRESS,
+        config: ConfigSnapshot::default(),
+        calibration: invalid[1],
+    };
+    match Veml6075::resume(I2cMock::new(&[]), token) {
+        Err(Error::InvalidCalibration) => (),
+        _ => panic!("Invalid calibration expected"),
+    }
+}
+
+#[test]
+fn can_create_cover_calibration() {
+    let none = Calibration::for_cover(CoverType::None);
+    assert_eq!(1.0, none.uva_transmission);
+    assert_eq!(1.0, none.uvb_transmission);
+    let c = Calibration::for_cover(CoverType::SodaLimeGlass);
+    assert_eq!(0.8, c.uva_transmission);
+    assert_eq!(0.1, c.uvb_transmission);
+    assert_eq!(none.uva_visible, c.uva_visible);
+}
+
+static GLASS_CALIBRATION: Calibration = Calibration::for_cover(CoverType::SodaLimeGlass);
+const CUSTOM_CALIBRATION: Calibration = Calibration::new(2.0, 1.0, 3.0, 1.5, 0.001, 0.002)
+    .with_angular_correction(1.2)
+    .with_transmission(0.9, 0.8);
+
+#[test]
+fn can_create_const_calibration() {
+    assert_eq!(0.8, GLASS_CALIBRATION.uva_transmission);
+    assert_eq!(2.0, CUSTOM_CALIBRATION.uva_visible);
+    assert_eq!(1.2, CUSTOM_CALIBRATION.angular_correction);
+    assert_eq!(0.8, CUSTOM_CALIBRATION.uvb_transmission);
+}
+
+#[test]
+fn calibration_wizard_fits_responsivity() {
+    let mut wizard = CalibrationWizard::new(Calibration::for_cover(CoverType::None), 2);
+    let raw = RawMeasurement {
+        uva: 1000,
+        uvb: 2000,
+        uvcomp1: 0,
+        uvcomp2: 0,
+    };
+    let reference = Calibration::for_cover(CoverType::None).compensate(&RawMeasurement {
+        uva: 1100,
+        uvb: 1800,
+        uvcomp1: 0,
+        uvcomp2: 0,
+    });
+    assert_eq!(
+        WizardState::Collecting {
+            collected: 1,
+            required: 2
+        },
+        wizard.add_sample(&raw, &reference)
+    );
+    match wizard.add_sample(&raw, &reference) {
+        WizardState::Done(c) => {
+            assert!((c.uva_responsivity - 0.001_461 * 1.1).abs() < 1e-7);
+            assert!((c.uvb_responsivity - 0.002_591 * 0.9).abs() < 1e-7);
+        }
+        _ => panic!("Calibration expected"),
+    }
+    wizard.reset();
+    let dark = RawMeasurement {
+        uva: 0,
+        uvb: 0,
+        ..raw
+    };
+    wizard.add_sample(&dark, &reference);
+    assert_eq!(WizardState::Failed, wizard.add_sample(&dark, &reference));
+}
+
+#[test]
+fn calibration_wizard_matches_reference_uv_index() {
+    let base = Calibration::for_cover(CoverType::None);
+    let golden = Calibration::new(2.22, 1.33, 2.95, 1.74, 0.001_461 * 1.2, 0.002_591 * 1.2);
+    let mut wizard = CalibrationWizard::new(base, 2);
+    let samples = [(1000, 2000, 1100, 1800), (500, 800, 550, 720)];
+    let mut state = WizardState::Failed;
+    for (uva, uvb, ref_uva, ref_uvb) in samples.iter() {
+        let raw = RawMeasurement {
+            uva: *uva,
+            uvb: *uvb,
+            uvcomp1: 0,
+            uvcomp2: 0,
+        };
+        let reference = golden.compensate(&RawMeasurement {
+            uva: *ref_uva,
+            uvb: *ref_uvb,
+            uvcomp1: 0,
+            uvcomp2: 0,
+        });
+        state = wizard.add_sample(&raw, &reference);
+    }
+    match state {
+        WizardState::Done(c) => {
+            assert!((c.uva_responsivity - 0.001_461 * 1.1 * 1.2).abs() < 1e-7);
+            assert!((c.uvb_responsivity - 0.002_591 * 0.9 * 1.2).abs() < 1e-7);
+            let m = c.compensate(&RawMeasurement {
+                uva: 1000,
+                uvb: 2000,
+                uvcomp1: 0,
+                uvcomp2: 0,
+            });
+            let reference = golden.compensate(&RawMeasurement {
+                uva: 1100,
+                uvb: 1800,
+                uvcomp1: 0,
+                uvcomp2: 0,
+            });
+            assert!((m.uv_index - reference.uv_index).abs() < 1e-4);
+        }
+        _ => panic!("Calibration expected"),
+    }
+}
+
+#[test]
+fn can_verify_config() {
+    let transactions = [
+        I2cTrans::write(DEVICE_ADDRESS, vec![Register::CONFIG, 0, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::CONFIG], vec![0, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::CONFIG], vec![0b0000_0001, 0]),
+    ];
+    let mut dev = new(&transactions);
+    dev.enable().unwrap();
+    dev.verify_config().unwrap();
+    match dev.verify_config() {
+        Err(Error::ConfigMismatch {
+            expected: 0,
+            actual: 1,
+        }) => (),
+        _ => panic!("Config mismatch expected"),
+    }
+    destroy(dev);
+}
+
+#[test]
+fn double_read_detects_mismatch() {
+    let uva = |value| I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVA], vec![value, 0]);
+    let transactions = [
+        uva(1),
+        uva(1),
+        uva(1),
+        uva(9),
+        uva(1),
+        uva(1),
+        uva(2),
+        uva(3),
+    ];
+    let mut dev = new(&transactions);
+    dev.set_double_read(true);
+    assert_eq!(1, dev.read_uva_raw().unwrap());
+    assert_eq!(1, dev.read_uva_raw().unwrap());
+    assert_eq!(1, dev.read_mismatches());
+    match dev.read_uva_raw() {
+        Err(Error::ReadMismatch) => (),
+        _ => panic!("Read mismatch expected"),
+    }
+    assert_eq!(2, dev.read_mismatches());
+    destroy(dev);
+}
+
+#[test]
+fn can_check_headroom() {
+    let raw = |uva| RawMeasurement {
+        uva,
+        uvb: 10,
+        uvcomp1: 10,
+        uvcomp2: 10,
+    };
+    let h = check_headroom(&raw(1000), IT::Ms100, DS::Normal, 0.2);
+    assert_eq!(
+        (IT::Ms800, DS::Normal),
+        (h.integration_time, h.dynamic_setting)
+    );
+    assert!(!h.saturated);
+    let h = check_headroom(&raw(20000), IT::Ms100, DS::Normal, 0.2);
+    assert_eq!(
+        (IT::Ms400, DS::High),
+        (h.integration_time, h.dynamic_setting)
+    );
+    let h = check_headroom(&raw(0xFFFF), IT::Ms50, DS::Normal, 0.2);
+    assert_eq!(
+        (IT::Ms50, DS::High),
+        (h.integration_time, h.dynamic_setting)
+    );
+    assert!(h.saturated);
+    assert_eq!(1.0, h.full_scale_fraction);
+    let dev = new(&[]);
+    assert_eq!(DS::Normal, dev.dynamic_setting());
+    let h = dev.check_headroom(&raw(1000), 0.2);
+    assert_eq!(IT::Ms800, h.integration_time);
+    destroy(dev);
+}
+
+#[test]
+fn auto_exposure_keeps_counts_in_band() {
+    let transactions = [
+        I2cTrans::write(DEVICE_ADDRESS, vec![Register::CONFIG, 0b0100_0001, 0]),
+        I2cTrans::write(DEVICE_ADDRESS, vec![Register::CONFIG, 0b0011_0001, 0]),
+    ];
+    let mut dev = new(&transactions);
+    dev.set_integration_time(IT::Ms800).unwrap();
+    let auto = AutoExposure::default();
+    let raw = |uva| RawMeasurement {
+        uva,
+        uvb: 0,
+        uvcomp1: 0,
+        uvcomp2: 0,
+    };
+    assert_eq!(None, auto.update(&mut dev, &raw(1000)).unwrap());
+    assert_eq!(Some(IT::Ms400), auto.update(&mut dev, &raw(60000)).unwrap());
+    assert_eq!(None, auto.update(&mut dev, &raw(30000)).unwrap());
+    // Below the band but doubling would exceed it
+    let narrow = AutoExposure::new(0.5, 0.8);
+    assert_eq!(None, narrow.update(&mut dev, &raw(30000)).unwrap());
+    destroy(dev);
+}
+
+#[test]
+fn can_read_hdr() {
+    let read = |uva: [u8; 2], uvb: [u8; 2]| {
+        vec![
+            I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVA], uva.to_vec()),
+            I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVB], uvb.to_vec()),
+            I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVCOMP1], vec![0, 0]),
+            I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVCOMP2], vec![0, 0]),
+        ]
+    };
+    let mut transactions = vec![I2cTrans::write(
+        DEVICE_ADDRESS,
+        vec![Register::CONFIG, 0, 0],
+    )];
+    transactions.push(I2cTrans::write(
+        DEVICE_ADDRESS,
+        vec![Register::CONFIG, 0, 0],
+    ));
+    transactions.extend(read([0xFF, 0xFF], [100, 0]));
+    transactions.push(I2cTrans::write(
+        DEVICE_ADDRESS,
+        vec![Register::CONFIG, 0b0000_1000, 0],
+    ));
+    transactions.extend(read([0x00, 0x90], [50, 0]));
+    transactions.push(I2cTrans::write(
+        DEVICE_ADDRESS,
+        vec![Register::CONFIG, 0, 0],
+    ));
+    let mut dev = new(&transactions);
+    dev.set_calibration(Calibration::new(0.0, 0.0, 0.0, 0.0, 1.0, 1.0))
+        .unwrap();
+    dev.enable().unwrap();
+    let m = dev.read_hdr(&mut MockNoop::new()).unwrap();
+    assert_eq!(73728.0, m.uva);
+    assert_eq!(100.0, m.uvb);
+    destroy(dev);
+}
+
+#[test]
+fn can_read_bracketed() {
+    let read = |uva: [u8; 2], uvb: [u8; 2]| {
+        vec![
+            I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVA], uva.to_vec()),
+            I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVB], uvb.to_vec()),
+            I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVCOMP1], vec![0, 0]),
+            I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVCOMP2], vec![0, 0]),
+        ]
+    };
+    let config = |value| I2cTrans::write(DEVICE_ADDRESS, vec![Register::CONFIG, value, 0]);
+    let mut transactions = vec![config(0b0001_0001)];
+    transactions.push(config(0b0000_0001));
+    transactions.extend(read([0x00, 0x90], [10, 0]));
+    transactions.push(config(0b0100_0001));
+    transactions.extend(read([0xFF, 0xFF], [160, 0]));
+    transactions.push(config(0b0001_0001));
+    let mut dev = new(&transactions);
+    dev.set_calibration(Calibration::new(0.0, 0.0, 0.0, 0.0, 1.0, 1.0))
+        .unwrap();
+    dev.set_integration_time(IT::Ms100).unwrap();
+    let m = dev
+        .read_bracketed(&mut MockNoop::new(), &[IT::Ms50, IT::Ms800])
+        .unwrap();
+    // Scaled to 100 ms: UVA from 50 ms, UVB from 800 ms
+    assert_eq!(73728.0, m.uva);
+    assert_eq!(20.0, m.uvb);
+    destroy(dev);
+}
+
+#[test]
+fn can_snapshot_and_restore() {
+    let transactions = [
+        I2cTrans::write(DEVICE_ADDRESS, vec![Register::CONFIG, 0b0011_0001, 0]),
+        I2cTrans::write(DEVICE_ADDRESS, vec![Register::CONFIG, 0b0011_0000, 0]),
+        I2cTrans::write(DEVICE_ADDRESS, vec![Register::CONFIG, 0b0000_1010, 0]),
+        I2cTrans::write(DEVICE_ADDRESS, vec![Register::CONFIG, 0b0011_0000, 0]),
+    ];
+    let mut dev = new(&transactions);
+    dev.set_integration_time(IT::Ms400).unwrap();
+    dev.enable().unwrap();
+    let snapshot = dev.snapshot();
+    assert_eq!(
+        ConfigSnapshot {
+            integration_time: IT::Ms400,
+            dynamic_setting: DS::Normal,
+            mode: Mode::Continuous,
+            enabled: true,
+        },
+        snapshot
+    );
+    dev.restore(ConfigSnapshot {
+        integration_time: IT::Ms50,
+        dynamic_setting: DS::High,
+        mode: Mode::ActiveForce,
+        enabled: true,
+    })
+    .unwrap();
+    dev.restore(snapshot).unwrap();
+    destroy(dev);
+}
+
+#[test]
+fn can_suspend_and_resume() {
+    let transactions = [
+        I2cTrans::write(0x20, vec![Register::CONFIG, 0b0010_0001, 0]),
+        I2cTrans::write(0x20, vec![Register::CONFIG, 0b0010_0000, 0]),
+        I2cTrans::write(0x20, vec![Register::CONFIG, 0b0010_0001, 0]),
+    ];
+    let calibration = Calibration::for_cover(CoverType::Ptfe);
+    let mut dev = Veml6075::new_with_address(I2cMock::new(&transactions), 0x20, calibration);
+    dev.set_integration_time(IT::Ms200).unwrap();
+    dev.enable().unwrap();
+    let token = dev.suspend().unwrap();
+    assert!(token.config.enabled);
+    dev.destroy().done();
+    let transactions = [I2cTrans::write(
+        0x20,
+        vec![Register::CONFIG, 0b0010_0000, 0],
+    )];
+    let dev = Veml6075::resume(I2cMock::new(&transactions), token).unwrap();
+    assert_eq!(calibration, dev.calibration());
+    assert_eq!(IT::Ms200, dev.integration_time());
+    destroy(dev);
+}
+
+#[test]
+fn can_init() {
+    let transactions = [
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::DEVICE_ID], vec![0x26, 0]),
+        I2cTrans::write(DEVICE_ADDRESS, vec![Register::CONFIG, 0b0001_0000, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVA], vec![0, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVB], vec![0, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVCOMP1], vec![0, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVCOMP2], vec![0, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVA], vec![0, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVB], vec![0, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVCOMP1], vec![0, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVCOMP2], vec![0, 0]),
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::DEVICE_ID], vec![0x70, 0]),
+    ];
+    let mut dev = new(&transactions);
+    let mut delay = RecordingDelay(0);
+    dev.init(&mut delay).unwrap();
+    assert_eq!(100, delay.0);
+    assert_eq!(ConfigSnapshot::default(), dev.snapshot());
+    dev.read_blocking(&mut delay).unwrap();
+    assert_eq!(100, delay.0);
+    match dev.init(&mut MockNoop::new()) {
+        Err(Error::InvalidDevice) => (),
+        _ => panic!("Invalid device expected"),
+    }
+    destroy(dev);
+}
+
+#[test]
+fn can_build() {
+    let transactions = [I2cTrans::write(
+        0x20,
+        vec![Register::CONFIG, 0b0100_0011, 0],
+    )];
+    let dev = Veml6075::builder()
+        .address(0x20)
+        .integration_time(IT::Ms800)
+        .mode(Mode::ActiveForce)
+        .enabled(false)
+        .calibration(Calibration::for_cover(CoverType::Pmma))
+        .build(I2cMock::new(&transactions))
+        .unwrap();
+    assert_eq!(Calibration::for_cover(CoverType::Pmma), dev.calibration());
+    destroy(dev);
+}
+
+#[test]
+fn can_load_config() {
+    let transactions = [
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::CONFIG], vec![0b0011_1010, 0]),
+        I2cTrans::write(DEVICE_ADDRESS, vec![Register::CONFIG, 0b0011_1011, 0]),
+    ];
+    let mut dev = new(&transactions);
+    dev.load_config().unwrap();
+    dev.disable().unwrap();
+    destroy(dev);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn logger_buffers_and_backs_off() {
+    use std::time::Duration;
+    use veml6075::logger::{JsonLines, LogError, Logger};
+    let read = |uva| {
+        vec![
+            I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVA], vec![uva, 0]),
+            I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVB], vec![0, 0]),
+        ]
+    };
+    let mut transactions = read(1);
+    transactions.push(
+        I2cTrans::write_read(DEVICE_ADDRESS, vec![Register::UVA], vec![0, 0])
+            .with_error(MockError::Io(ErrorKind::Other)),
+    );
+    transactions.extend(read(2));
+    let mut dev = new(&transactions);
+    dev.set_calibration(Calibration::new(0.0, 0.0, 0.0, 0.0, 1.0, 1.0))
+        .unwrap();
+    let storage = JsonLines::new(Vec::new());
+    let mut logger = Logger::new(dev, storage, FixedClock(5), Duration::from_secs(1));
+    logger.set_buffer_len(2);
+    logger.sample().unwrap();
+    assert_eq!(1, logger.pending());
+    match logger.sample() {
+        Err(LogError::Sensor(_)) => (),
+        _ => panic!("Sensor error expected"),
+    }
+    assert_eq!(Duration::from_secs(2), logger.next_delay());
+    logger.sample().unwrap();
+    assert_eq!(0, logger.pending());
+    assert_eq!(Duration::from_secs(1), logger.next_delay());
+    let (dev, storage) = logger.destroy();
+    destroy(dev);
+    assert_eq!(
+        "{\"at\":5,\"uva\":1,\"uvb\":0,\"uvi\":0.5}\n\
+         {\"at\":5,\"uva\":2,\"uvb\":0,\"uvi\":1}\n",
+        String::from_utf8(storage.destroy()).unwrap()
     );
 }